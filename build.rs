@@ -0,0 +1,53 @@
+use std::{env, fs, path::Path};
+
+/// The files `wasm-bindgen --target web --out-name presentrs` writes.
+const CLIENT_FILES: [&str; 2] = ["presentrs.js", "presentrs_bg.wasm"];
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=PRESENTRS_CLIENT_DIR");
+    println!("cargo:rustc-check-cfg=cfg(presentrs_client_dir)");
+
+    if env::var_os("CARGO_FEATURE_EMBEDDED_CLIENT").is_none() {
+        return;
+    }
+
+    let client_dir = match env::var_os("PRESENTRS_CLIENT_DIR") {
+        Some(client_dir) => client_dir,
+        None => {
+            println!(
+                "cargo:warning=PRESENTRS_CLIENT_DIR is not set, so only the \
+                 shell page is embedded and the client must be served from \
+                 the assets directory"
+            );
+            return;
+        }
+    };
+
+    let client_dir = fs::canonicalize(&client_dir).unwrap_or_else(|error| {
+        panic!(
+            "PRESENTRS_CLIENT_DIR {:?} is not a readable directory: {}",
+            Path::new(&client_dir),
+            error,
+        )
+    });
+
+    for file in &CLIENT_FILES {
+        let path = client_dir.join(file);
+
+        if !path.is_file() {
+            panic!(
+                "PRESENTRS_CLIENT_DIR {:?} has no {}, build the client into \
+                 it with `wasm-bindgen --target web --out-name presentrs`",
+                client_dir, file,
+            );
+        }
+
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+
+    println!(
+        "cargo:rustc-env=PRESENTRS_EMBEDDED_CLIENT_DIR={}",
+        client_dir.display(),
+    );
+    println!("cargo:rustc-cfg=presentrs_client_dir");
+}
@@ -0,0 +1,97 @@
+use {
+    super::slide_size::SlideSize,
+    crate::sync_message::{Annotation, SlidePoint},
+    yew::prelude::*,
+};
+
+const ANNOTATION_COLOR: &str = "rgb(220, 30, 30)";
+
+/// The presenter's pointer and drawings over the current slide.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Annotations {
+    pointer: Option<SlidePoint>,
+    strokes: Vec<Stroke>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Stroke {
+    id: u32,
+    points: Vec<SlidePoint>,
+}
+
+impl Annotations {
+    pub fn apply(&mut self, annotation: Annotation) {
+        match annotation {
+            Annotation::Pointer { point } => self.pointer = point,
+            Annotation::Stroke { id, points } => {
+                match self.strokes.iter_mut().find(|stroke| stroke.id == id) {
+                    Some(stroke) => stroke.points.extend(points),
+                    None => self.strokes.push(Stroke { id, points }),
+                }
+            }
+            Annotation::Clear => self.strokes.clear(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.pointer = None;
+        self.strokes.clear();
+    }
+
+    pub fn view(&self, size: &SlideSize) -> Html {
+        let (width, height) = size.logical_size();
+        let to_pixels = |point: &SlidePoint| {
+            format!("{},{}", point.x * width, point.y * height)
+        };
+
+        html! {
+            <svg
+                viewBox = format!("0 0 {} {}", width, height)
+                style = "\
+                    position: absolute;\
+                    top: 0;\
+                    left: 0;\
+                    width: 100%;\
+                    height: 100%;\
+                    pointer-events: none;\
+                "
+                >
+                {
+                    for self.strokes.iter().map(|stroke| {
+                        let points: Vec<_> =
+                            stroke.points.iter().map(to_pixels).collect();
+
+                        html! {
+                            <polyline
+                                points = points.join(" ")
+                                style = format!("\
+                                    fill: none;\
+                                    stroke: {};\
+                                    stroke-width: 4;\
+                                    stroke-linecap: round;\
+                                    stroke-linejoin: round;\
+                                ", ANNOTATION_COLOR)
+                                />
+                        }
+                    })
+                }
+                {
+                    match self.pointer {
+                        Some(point) => html! {
+                            <circle
+                                cx = (point.x * width).to_string()
+                                cy = (point.y * height).to_string()
+                                r = 8
+                                style = format!(
+                                    "fill: {}; opacity: 0.8;",
+                                    ANNOTATION_COLOR,
+                                )
+                                />
+                        },
+                        None => html! {},
+                    }
+                }
+            </svg>
+        }
+    }
+}
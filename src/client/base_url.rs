@@ -0,0 +1,35 @@
+use web_sys::Url;
+
+/// Resolves a path of the deck against the document's base URL.
+///
+/// The base URL is the page's own URL unless the shell page sets one with a
+/// `<base href="/talks/rust-async/">` element, which allows decks to be
+/// served from a sub-path.
+pub fn resolve(path: &str) -> String {
+    resolve_url(path)
+        .map(|url| url.href())
+        .unwrap_or_else(|| path.to_owned())
+}
+
+/// Resolves a path of the deck into a WebSocket URL, using `wss:` when the
+/// page was loaded over HTTPS.
+pub fn resolve_web_socket(path: &str) -> String {
+    match resolve_url(path) {
+        Some(url) => {
+            let protocol = match url.protocol().as_str() {
+                "https:" => "wss:",
+                _ => "ws:",
+            };
+
+            url.set_protocol(protocol);
+            url.href()
+        }
+        None => path.to_owned(),
+    }
+}
+
+fn resolve_url(path: &str) -> Option<Url> {
+    let base = web_sys::window()?.document()?.base_uri().ok()??;
+
+    Url::new_with_base(path, &base).ok()
+}
@@ -0,0 +1,67 @@
+use {
+    wasm_bindgen::{closure::Closure, JsCast},
+    web_sys::{Document, Event},
+    yew::Callback,
+};
+
+pub struct FullscreenService;
+
+impl FullscreenService {
+    fn document() -> Option<Document> {
+        web_sys::window()?.document()
+    }
+
+    pub fn is_active() -> bool {
+        Self::document()
+            .and_then(|document| document.fullscreen_element())
+            .is_some()
+    }
+
+    pub fn toggle() {
+        let document = match Self::document() {
+            Some(document) => document,
+            None => return,
+        };
+
+        if document.fullscreen_element().is_some() {
+            document.exit_fullscreen();
+        } else if let Some(element) = document.document_element() {
+            let _ = element.request_fullscreen();
+        }
+    }
+
+    pub fn register(callback: Callback<bool>) -> FullscreenTask {
+        let listener = Closure::wrap(Box::new(move |_: Event| {
+            callback.emit(Self::is_active());
+        }) as Box<dyn FnMut(Event)>);
+
+        if let Some(document) = Self::document() {
+            let _ = document.add_event_listener_with_callback(
+                FullscreenTask::EVENT,
+                listener.as_ref().unchecked_ref(),
+            );
+        }
+
+        FullscreenTask { listener }
+    }
+}
+
+#[must_use = "the listener is only active until the task is dropped"]
+pub struct FullscreenTask {
+    listener: Closure<dyn FnMut(Event)>,
+}
+
+impl FullscreenTask {
+    const EVENT: &'static str = "fullscreenchange";
+}
+
+impl Drop for FullscreenTask {
+    fn drop(&mut self) {
+        if let Some(document) = FullscreenService::document() {
+            let _ = document.remove_event_listener_with_callback(
+                Self::EVENT,
+                self.listener.as_ref().unchecked_ref(),
+            );
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use {
+    std::fmt::{self, Display, Formatter},
+    wasm_bindgen::{closure::Closure, JsCast, JsValue},
+    web_sys::Event,
+    yew::Callback,
+};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Position {
+    pub locale: Option<String>,
+    pub slide: usize,
+    pub step: usize,
+}
+
+impl Position {
+    pub fn from_hash(hash: &str) -> Option<Self> {
+        let path = hash.trim_start_matches('#').trim_start_matches('/');
+        let mut segments =
+            path.split('/').filter(|segment| !segment.is_empty());
+
+        let mut first = segments.next()?;
+        let mut locale = None;
+
+        if first.parse::<usize>().is_err() {
+            locale = Some(first.to_owned());
+            first = segments.next()?;
+        }
+
+        let slide = first.parse().ok().filter(|&slide| slide > 0)?;
+        let step = match segments.next() {
+            Some(step) => step.parse().ok().filter(|&step| step > 0)?,
+            None => 1,
+        };
+
+        Some(Position {
+            locale,
+            slide,
+            step,
+        })
+    }
+
+    pub fn to_hash(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "#/")?;
+
+        if let Some(ref locale) = self.locale {
+            write!(formatter, "{}/", locale)?;
+        }
+
+        write!(formatter, "{}/{}", self.slide, self.step)
+    }
+}
+
+pub struct LocationService;
+
+impl LocationService {
+    pub fn current() -> Option<Position> {
+        let hash = web_sys::window()?.location().hash().ok()?;
+
+        Position::from_hash(&hash)
+    }
+
+    pub fn push(position: &Position) {
+        Self::update(position, true);
+    }
+
+    pub fn replace(position: &Position) {
+        Self::update(position, false);
+    }
+
+    fn update(position: &Position, new_entry: bool) {
+        let window = match web_sys::window() {
+            Some(window) => window,
+            None => return,
+        };
+        let hash = position.to_hash();
+
+        if window.location().hash().ok().as_deref() == Some(hash.as_str()) {
+            return;
+        }
+
+        if let Ok(history) = window.history() {
+            let _ = if new_entry {
+                history.push_state_with_url(&JsValue::NULL, "", Some(&hash))
+            } else {
+                history.replace_state_with_url(&JsValue::NULL, "", Some(&hash))
+            };
+        }
+    }
+
+    pub fn register(callback: Callback<Position>) -> LocationTask {
+        let listener = Closure::wrap(Box::new(move |_: Event| {
+            if let Some(position) = Self::current() {
+                callback.emit(position);
+            }
+        }) as Box<dyn FnMut(Event)>);
+
+        if let Some(window) = web_sys::window() {
+            for event in LocationTask::EVENTS {
+                let _ = window.add_event_listener_with_callback(
+                    event,
+                    listener.as_ref().unchecked_ref(),
+                );
+            }
+        }
+
+        LocationTask { listener }
+    }
+}
+
+#[must_use = "the listener is only active until the task is dropped"]
+pub struct LocationTask {
+    listener: Closure<dyn FnMut(Event)>,
+}
+
+impl LocationTask {
+    /// Browsers also send `hashchange` when going back to a different hash,
+    /// but `popstate` alone covers both history navigation and edits to the
+    /// hash without reporting them twice.
+    const EVENTS: [&'static str; 1] = ["popstate"];
+}
+
+impl Drop for LocationTask {
+    fn drop(&mut self) {
+        if let Some(window) = web_sys::window() {
+            for event in Self::EVENTS {
+                let _ = window.remove_event_listener_with_callback(
+                    event,
+                    self.listener.as_ref().unchecked_ref(),
+                );
+            }
+        }
+    }
+}
@@ -1,37 +1,114 @@
+mod annotations;
+mod base_url;
+mod fullscreen;
+mod location;
 mod navigation;
 mod navigation_button;
 mod notes;
+mod playback;
+mod questions;
+mod reactions;
+mod search;
 mod slide;
 mod slide_size;
 mod slide_sync;
 mod slides;
+mod styles;
+mod touch;
+mod transitions;
+
+pub use crate::key_bindings::{KeyAction, KeyBindings};
 
 use {
     self::{
-        navigation::Navigation, notes::Notes, slide_size::SlideSize,
+        annotations::Annotations,
+        fullscreen::{FullscreenService, FullscreenTask},
+        location::{LocationService, LocationTask, Position},
+        navigation::Navigation,
+        notes::Notes,
+        playback::Playback,
+        reactions::{FloatingReactions, REACTION_DURATION},
+        search::SearchPalette,
+        slide_size::SlideSize,
         slides::Slides,
+        touch::{Gesture, SwipeDirection, TouchPoint, TouchTracker},
+    },
+    crate::{
+        config::{DeckConfig, SlideFit, SlideFormat},
+        manifest::{Manifest, SlideSummary},
+        navigator::Navigator,
+        sync_message::{Annotation, ClientMessage, PollResults, Reaction},
+    },
+    std::{
+        collections::{BTreeMap, VecDeque},
+        mem,
+        rc::Rc,
+        time::Duration,
     },
-    lru::LruCache,
+    wasm_bindgen::JsCast,
+    web_sys::{Element, HtmlElement, TouchList},
     yew::{
+        format::{Nothing, Text},
         prelude::*,
-        services::resize::{ResizeService, ResizeTask, WindowDimensions},
+        services::{
+            fetch::{FetchService, FetchTask, Request, Response},
+            resize::{ResizeService, ResizeTask, WindowDimensions},
+            timeout::{TimeoutService, TimeoutTask},
+            ConsoleService,
+        },
     },
 };
 
-const SLIDE_WIDTH: f64 = 800.0;
-const SLIDE_HEIGHT: f64 = 600.0;
+const CONTROLS_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Mounts the presentation on the page when the client is loaded by the
+/// bundled shell page.
+#[cfg(feature = "embedded-client")]
+#[wasm_bindgen::prelude::wasm_bindgen(start)]
+pub fn start() {
+    yew::start_app::<Presentrs>();
+}
 
 pub enum Message {
     ToggleNotes,
-    SlideLoaded(usize, usize),
+    SlideLoaded(usize, usize, SlideSummary),
+    ManifestLoaded(Manifest),
+    ConfigLoaded(Option<DeckConfig>),
     FirstSlide,
+    LastSlide,
+    JumpToSlide(usize),
+    TypeDigit(usize),
+    JumpToTypedSlide,
+    OpenSearch,
+    CloseSearch,
+    CancelInput,
+    ToggleBlank,
+    ToggleHelp,
+    ToggleFullscreen,
+    FullscreenChanged(bool),
+    ShowControls,
+    HideControls,
     PreviousSlide,
     PreviousStep,
     NextSlide,
     NextStep,
     Resize(WindowDimensions),
     TogglePresent,
+    PresentingRefused,
     ChangePosition { slide: u16, step: u16 },
+    LocationChanged(Position),
+    TouchStart(Vec<TouchPoint>, f64),
+    TouchMove(Vec<TouchPoint>),
+    TouchEnd(usize, f64),
+    Annotate(Annotation),
+    AnnotationReceived(Annotation),
+    AnnotationSenderReady(Callback<Annotation>),
+    Vote(u32, u32),
+    PollResultsReceived(PollResults),
+    React(Reaction),
+    ReactionsReceived(BTreeMap<Reaction, u32>),
+    ReactionsExpired(u64),
+    MessageSenderReady(Callback<ClientMessage>),
     Ignore,
 }
 
@@ -39,39 +116,407 @@ pub struct Presentrs {
     component_link: ComponentLink<Self>,
     locale: Option<String>,
     locales: Vec<String>,
-    current_slide: usize,
-    current_step: usize,
-    slide_steps_cache: LruCache<usize, usize>,
+    navigator: Navigator,
+    annotations: Rc<Annotations>,
+    annotation_sender: Option<Callback<Annotation>>,
+    poll_results: Rc<BTreeMap<u32, PollResults>>,
+    message_sender: Option<Callback<ClientMessage>>,
+    reactions: FloatingReactions,
+    reaction_timeouts: VecDeque<TimeoutTask>,
     slide_size: SlideSize,
     show_notes: bool,
     presenting: bool,
+    pending_history_entry: bool,
+    manifest: Option<Manifest>,
+    loaded_summaries: BTreeMap<usize, SlideSummary>,
+    typed_slide: Option<usize>,
+    searching: bool,
+    refocus: bool,
+    root: NodeRef,
+    blank: bool,
+    show_help: bool,
+    base_key_bindings: KeyBindings,
+    base_slide_format: SlideFormat,
+    base_slide_fit: SlideFit,
+    key_bindings: KeyBindings,
+    deck_config: Option<DeckConfig>,
+    config_task: Option<FetchTask>,
+    touch_tracker: TouchTracker,
+    viewport_width: f64,
+    fullscreen: bool,
+    controls_visible: bool,
+    controls_timeout: Option<TimeoutTask>,
     _resize_listener: ResizeTask,
+    _fullscreen_listener: FullscreenTask,
+    _location_listener: LocationTask,
 }
 
 impl Presentrs {
     fn resize(&mut self, dimensions: WindowDimensions) {
+        self.viewport_width = dimensions.width as f64;
         self.slide_size.resize_to_fit_in(
             dimensions.width as f64,
             dimensions.height as f64,
         );
     }
 
-    fn on_key_down(event: KeyboardEvent) -> Message {
-        let message = match event.key().as_str() {
-            "ArrowLeft" | "PageUp" => Message::PreviousStep,
-            "ArrowRight" | "PageDown" => Message::NextStep,
-            "ArrowUp" => Message::PreviousSlide,
-            "ArrowDown" => Message::NextSlide,
-            "Home" => Message::FirstSlide,
-            "n" => Message::ToggleNotes,
-            "p" => Message::TogglePresent,
-            _ => return Message::Ignore,
+    fn on_key_down(
+        key_bindings: &KeyBindings,
+        event: KeyboardEvent,
+    ) -> Message {
+        if event.ctrl_key() || event.alt_key() || event.meta_key() {
+            return Message::Ignore;
+        }
+
+        let key = event.key();
+        let message = match key_bindings.action_for(&key) {
+            Some(action) => action.into(),
+            None => match key.parse() {
+                Ok(digit) if key.len() == 1 => Message::TypeDigit(digit),
+                _ => return Message::Ignore,
+            },
         };
 
         event.prevent_default();
 
         message
     }
+
+    fn show_controls(&mut self) -> ShouldRender {
+        let was_visible = self.controls_visible;
+
+        self.controls_visible = true;
+        self.controls_timeout = Some(TimeoutService::spawn(
+            CONTROLS_TIMEOUT,
+            self.component_link.callback(|_| Message::HideControls),
+        ));
+
+        !was_visible
+    }
+
+    fn fit_to_window(&mut self) {
+        if let Some(window) = web_sys::window() {
+            self.resize(WindowDimensions::get_dimensions(&window));
+        }
+    }
+
+    fn touch_points(touches: TouchList) -> Vec<TouchPoint> {
+        (0..touches.length())
+            .filter_map(|index| touches.get(index))
+            .map(|touch| TouchPoint {
+                x: touch.client_x() as f64,
+                y: touch.client_y() as f64,
+            })
+            .collect()
+    }
+
+    /// Whether a touch started on the slides or the space around them, and
+    /// not on the controls and panels shown over them, which handle their own
+    /// touches.
+    fn is_slide_touch(event: &TouchEvent) -> bool {
+        let target = event
+            .target()
+            .and_then(|target| target.dyn_into::<Element>().ok());
+        let root = event
+            .current_target()
+            .and_then(|root| root.dyn_into::<Element>().ok());
+
+        match (target, root) {
+            (Some(target), Some(root)) => {
+                target == root
+                    || target.closest("#slide").ok().flatten().is_some()
+            }
+            _ => false,
+        }
+    }
+
+    fn on_touch_start(event: TouchEvent) -> Message {
+        if !Self::is_slide_touch(&event) {
+            return Message::Ignore;
+        }
+
+        Message::TouchStart(
+            Self::touch_points(event.touches()),
+            event.time_stamp(),
+        )
+    }
+
+    fn on_touch_move(event: TouchEvent) -> Message {
+        if !Self::is_slide_touch(&event) {
+            return Message::Ignore;
+        }
+
+        Message::TouchMove(Self::touch_points(event.touches()))
+    }
+
+    fn on_touch_end(event: TouchEvent) -> Message {
+        if !Self::is_slide_touch(&event) {
+            return Message::Ignore;
+        }
+
+        Message::TouchEnd(event.touches().length() as usize, event.time_stamp())
+    }
+
+    fn handle_gesture(&mut self, gesture: Option<Gesture>) -> ShouldRender {
+        let message = match gesture {
+            Some(Gesture::Swipe(SwipeDirection::Left)) => Message::NextStep,
+            Some(Gesture::Swipe(SwipeDirection::Right)) => {
+                Message::PreviousStep
+            }
+            Some(Gesture::Swipe(SwipeDirection::Up)) => Message::NextSlide,
+            Some(Gesture::Swipe(SwipeDirection::Down)) => {
+                Message::PreviousSlide
+            }
+            Some(Gesture::Tap(point)) => {
+                if point.x < self.viewport_width / 3.0 {
+                    Message::PreviousStep
+                } else if point.x > self.viewport_width * 2.0 / 3.0 {
+                    Message::NextStep
+                } else if self.slide_size.is_zoomed() {
+                    self.slide_size.reset_zoom();
+                    return true;
+                } else {
+                    return false;
+                }
+            }
+            Some(Gesture::Pinch(factor)) => {
+                self.slide_size.zoom_by(factor);
+                return true;
+            }
+            Some(Gesture::Pan { delta_x, delta_y }) => {
+                self.slide_size.pan_by(delta_x, delta_y);
+                return true;
+            }
+            None => return false,
+        };
+
+        Component::update(self, message)
+    }
+
+    fn fetch_config(component_link: &ComponentLink<Self>) -> Option<FetchTask> {
+        let request = Request::get(base_url::resolve(DeckConfig::FILE_NAME))
+            .body(Nothing)
+            .ok()?;
+
+        FetchService::fetch(
+            request,
+            component_link.callback(|response: Response<Text>| {
+                let (meta, body) = response.into_parts();
+
+                let config = body
+                    .ok()
+                    .filter(|_| meta.status.is_success())
+                    .and_then(|body| serde_json::from_str(&body).ok());
+
+                Message::ConfigLoaded(config)
+            }),
+        )
+        .ok()
+    }
+
+    fn apply_key_bindings(&mut self) {
+        let mut key_bindings = self.base_key_bindings.clone();
+
+        if let Some(ref config) = self.deck_config {
+            for error in key_bindings.apply_config(&config.key_bindings) {
+                ConsoleService::warn(&format!(
+                    "Ignoring key binding in {}: {}",
+                    DeckConfig::FILE_NAME,
+                    error,
+                ));
+            }
+        }
+
+        self.key_bindings = key_bindings;
+    }
+
+    fn apply_slide_format(&mut self) {
+        let config = self.deck_config.as_ref();
+        let format = config
+            .and_then(|config| config.slide_format)
+            .unwrap_or(self.base_slide_format);
+        let fit = config
+            .and_then(|config| config.slide_fit)
+            .unwrap_or(self.base_slide_fit);
+
+        self.slide_size.set_format(format, fit);
+        self.fit_to_window();
+    }
+
+    fn update_location(&mut self, new_history_entry: bool) {
+        // The step isn't known until the slide loads, so the history entry is
+        // added then instead.
+        if self.navigator.is_waiting_for_step_count() {
+            self.pending_history_entry |= new_history_entry;
+            return;
+        }
+
+        let new_history_entry =
+            new_history_entry || mem::take(&mut self.pending_history_entry);
+
+        let position = Position {
+            locale: self.locale.clone(),
+            slide: self.navigator.slide(),
+            step: self.navigator.step(),
+        };
+
+        if new_history_entry {
+            LocationService::push(&position);
+        } else {
+            LocationService::replace(&position);
+        }
+    }
+
+    fn search_entries(&self) -> Vec<(usize, SlideSummary)> {
+        let mut entries: BTreeMap<_, _> = self
+            .manifest
+            .iter()
+            .flat_map(|manifest| manifest.slides.iter().cloned())
+            .enumerate()
+            .map(|(index, summary)| (index + 1, summary))
+            .collect();
+
+        for (&slide, summary) in &self.loaded_summaries {
+            entries.insert(slide, summary.clone());
+        }
+
+        entries.into_iter().collect()
+    }
+
+    fn view_typed_slide(&self) -> Html {
+        match self.typed_slide {
+            Some(slide) => html! {
+                <div style="
+                    position: absolute;\
+                    top: 10px;\
+                    left: 10px;\
+                    padding: 4px 8px;\
+                    background: white;\
+                    border: 1px solid gray;\
+                ">
+                    { format!("Go to slide {}", slide) }
+                </div>
+            },
+            None => html! {},
+        }
+    }
+
+    /// Shows the playback controls if the deck replays a recorded talk.
+    // `html!` expands the properties of child components into statements
+    // that clippy considers unnecessary.
+    #[allow(clippy::unnecessary_operation)]
+    fn view_playback(&self, on_update_position: Callback<(u16, u16)>) -> Html {
+        let config = self
+            .deck_config
+            .as_ref()
+            .and_then(|config| config.playback.as_ref());
+
+        match config {
+            Some(config) => html! {
+                <Playback
+                    config = config.clone()
+                    on_update_position = on_update_position
+                    visible = self.controls_visible
+                    />
+            },
+            None => html! {},
+        }
+    }
+
+    /// Sends a message over the sync connection, if it's connected.
+    fn send(&self, message: ClientMessage) {
+        if let Some(sender) = &self.message_sender {
+            sender.emit(message);
+        }
+    }
+
+    fn view_blank(&self) -> Html {
+        if self.blank {
+            html! {
+                <div style="
+                    position: absolute;\
+                    left: 0;\
+                    right: 0;\
+                    top: 0;\
+                    bottom: 0;\
+                    background: black;\
+                    z-index: 20;\
+                "></div>
+            }
+        } else {
+            html! {}
+        }
+    }
+
+    fn view_help(&self) -> Html {
+        if !self.show_help {
+            return html! {};
+        }
+
+        let rows = self.key_bindings.keys_by_action().into_iter().map(
+            |(action, keys)| {
+                let keys = keys
+                    .into_iter()
+                    .map(key_label)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                html! {
+                    <tr>
+                        <td style="padding: 2px 12px 2px 0;">
+                            <strong>{ keys }</strong>
+                        </td>
+                        <td>{ action.description() }</td>
+                    </tr>
+                }
+            },
+        );
+
+        html! {
+            <div style="
+                position: absolute;\
+                top: 50%;\
+                left: 50%;\
+                transform: translate(-50%, -50%);\
+                padding: 16px;\
+                background: white;\
+                border: 1px solid gray;\
+                box-shadow: 0 2px 8px rgba(0, 0, 0, 0.3);\
+                z-index: 10;\
+            ">
+                <p><strong>{ "Keyboard shortcuts" }</strong></p>
+                <table>
+                    { for rows }
+                    <tr>
+                        <td style="padding: 2px 12px 2px 0;">
+                            <strong>{ "0-9" }</strong>
+                        </td>
+                        <td>{ "Type a slide number" }</td>
+                    </tr>
+                </table>
+            </div>
+        }
+    }
+
+    #[allow(clippy::unnecessary_operation)]
+    fn view_search(&self) -> Html {
+        if self.searching {
+            let select_callback =
+                self.component_link.callback(Message::JumpToSlide);
+            let close_callback =
+                self.component_link.callback(|_| Message::CloseSearch);
+
+            html! {
+                <SearchPalette
+                    entries = self.search_entries()
+                    on_select = select_callback
+                    on_close = close_callback
+                    />
+            }
+        } else {
+            html! {}
+        }
+    }
 }
 
 impl Component for Presentrs {
@@ -85,104 +530,293 @@ impl Component for Presentrs {
         let window = web_sys::window().expect("Failed to access window");
         let window_size = WindowDimensions::get_dimensions(&window);
         let resize_callback = component_link.callback(Message::Resize);
+        let config_task = Self::fetch_config(&component_link);
+        let fullscreen_callback =
+            component_link.callback(Message::FullscreenChanged);
+        let location_callback =
+            component_link.callback(Message::LocationChanged);
+        let position = LocationService::current();
+        let locale = position
+            .as_ref()
+            .and_then(|position| position.locale.as_ref())
+            .filter(|locale| properties.locales.contains(locale))
+            .or_else(|| properties.locales.first())
+            .cloned();
 
-        let mut slide_size = SlideSize::new(SLIDE_WIDTH, SLIDE_HEIGHT);
+        let mut slide_size = SlideSize::from_format(
+            properties.slide_format,
+            properties.slide_fit,
+        );
 
         slide_size.resize_to_fit_in(
             window_size.width as f64,
             window_size.height as f64,
         );
 
-        Presentrs {
+        let mut this = Presentrs {
             component_link,
-            locale: properties.locales.first().cloned(),
+            locale,
             locales: properties.locales,
-            current_slide: 1,
-            current_step: 1,
-            slide_steps_cache: LruCache::new(50),
+            navigator: position
+                .as_ref()
+                .map_or_else(Navigator::new, |position| {
+                    Navigator::at(position.slide, position.step)
+                }),
+            annotations: Rc::default(),
+            annotation_sender: None,
+            poll_results: Rc::default(),
+            message_sender: None,
+            reactions: FloatingReactions::default(),
+            reaction_timeouts: VecDeque::new(),
             slide_size,
             show_notes: false,
             presenting: false,
+            pending_history_entry: false,
+            manifest: None,
+            loaded_summaries: BTreeMap::new(),
+            typed_slide: None,
+            searching: false,
+            refocus: false,
+            root: NodeRef::default(),
+            blank: false,
+            show_help: false,
+            base_key_bindings: properties.key_bindings.clone(),
+            base_slide_format: properties.slide_format,
+            base_slide_fit: properties.slide_fit,
+            key_bindings: properties.key_bindings,
+            deck_config: None,
+            config_task,
+            touch_tracker: TouchTracker::default(),
+            viewport_width: window_size.width as f64,
+            fullscreen: FullscreenService::is_active(),
+            controls_visible: false,
+            controls_timeout: None,
             _resize_listener: ResizeService::register(resize_callback),
-        }
+            _fullscreen_listener: FullscreenService::register(
+                fullscreen_callback,
+            ),
+            _location_listener: LocationService::register(location_callback),
+        };
+
+        this.show_controls();
+        this
     }
 
     fn update(&mut self, message: Self::Message) -> ShouldRender {
+        let new_history_entry = matches!(
+            message,
+            Message::FirstSlide
+                | Message::LastSlide
+                | Message::JumpToSlide(_)
+                | Message::JumpToTypedSlide
+                | Message::PreviousSlide
+                | Message::PreviousStep
+                | Message::NextSlide
+                | Message::NextStep
+        );
+
+        let previous_slide = self.navigator.slide();
+
+        // Moving on abandons the slide number being typed, so that the next
+        // digit starts a new one.
+        let abandoned_typed_slide = (new_history_entry
+            || matches!(message, Message::LocationChanged(_)))
+            && !matches!(message, Message::JumpToTypedSlide)
+            && self.typed_slide.take().is_some();
+
         match message {
             Message::ToggleNotes => {
                 self.show_notes = !self.show_notes;
             }
-            Message::SlideLoaded(slide_index, num_steps) => {
-                self.slide_steps_cache.put(slide_index, num_steps.max(1));
-
-                if self.current_slide == slide_index
-                    && self.current_step == usize::MAX
-                {
-                    self.current_step = num_steps;
-                }
+            Message::SlideLoaded(slide_index, num_steps, summary) => {
+                self.navigator.set_step_count(slide_index, num_steps);
+                self.loaded_summaries.insert(slide_index, summary);
+            }
+            Message::ManifestLoaded(manifest) => {
+                self.navigator.set_slide_count(manifest.slide_count());
+                self.manifest = Some(manifest);
+            }
+            Message::ConfigLoaded(config) => {
+                self.config_task = None;
+                self.deck_config = config;
+                self.apply_key_bindings();
+                self.apply_slide_format();
             }
             Message::FirstSlide => {
-                if self.current_slide != 1 {
-                    self.current_slide = 1;
+                self.navigator.first_slide();
+            }
+            Message::LastSlide => {
+                if !self.navigator.last_slide() {
+                    return abandoned_typed_slide;
                 }
+            }
+            Message::JumpToSlide(slide) => {
+                self.navigator.jump_to_slide(slide);
+                self.searching = false;
+                self.refocus = true;
+            }
+            Message::TypeDigit(digit) => {
+                let typed_slide = self
+                    .typed_slide
+                    .unwrap_or(0)
+                    .saturating_mul(10)
+                    .saturating_add(digit);
 
-                self.current_step = 1;
+                self.typed_slide = Some(typed_slide);
             }
-            Message::PreviousSlide => {
-                if self.current_slide > 1 {
-                    self.current_slide -= 1;
+            Message::JumpToTypedSlide => match self.typed_slide.take() {
+                Some(slide) => {
+                    self.navigator.jump_to_slide(slide);
                 }
-
-                self.current_step = 1;
+                None => return false,
+            },
+            Message::OpenSearch => {
+                self.typed_slide = None;
+                self.searching = true;
+            }
+            Message::CloseSearch => {
+                self.searching = false;
+                self.refocus = true;
+            }
+            Message::CancelInput => {
+                self.typed_slide = None;
+                self.searching = false;
+                self.blank = false;
+                self.show_help = false;
+            }
+            Message::ToggleBlank => self.blank = !self.blank,
+            Message::ToggleHelp => self.show_help = !self.show_help,
+            Message::ToggleFullscreen => {
+                FullscreenService::toggle();
+                return false;
+            }
+            Message::FullscreenChanged(fullscreen) => {
+                self.fullscreen = fullscreen;
+                self.fit_to_window();
+                self.refocus = true;
+            }
+            Message::ShowControls => return self.show_controls(),
+            Message::HideControls => {
+                self.controls_timeout = None;
+                self.controls_visible = false;
+            }
+            Message::PreviousSlide => {
+                self.navigator.previous_slide();
             }
             Message::PreviousStep => {
-                if self.current_step > 1 {
-                    self.current_step -= 1;
-                } else if self.current_slide > 1 {
-                    self.current_slide -= 1;
-                    self.current_step = self
-                        .slide_steps_cache
-                        .peek(&self.current_slide)
-                        .copied()
-                        .unwrap_or(usize::MAX);
-                }
+                self.navigator.previous_step();
             }
             Message::NextStep => {
-                let last_step = self
-                    .slide_steps_cache
-                    .peek(&self.current_slide)
-                    .copied()
-                    .unwrap_or(usize::MAX);
-
-                if self.current_step < last_step {
-                    self.current_step += 1;
-                } else {
-                    self.current_slide += 1;
-                    self.current_step = 1;
-                }
+                self.navigator.next_step();
             }
             Message::NextSlide => {
-                self.current_slide += 1;
-                self.current_step = 1;
+                self.navigator.next_slide();
             }
             Message::ChangePosition { slide, step } => {
-                let slide: usize = slide.into();
+                self.navigator.change_position(slide.into(), step.into());
+            }
+            Message::LocationChanged(position) => {
+                if let Some(locale) = position.locale {
+                    if self.locales.contains(&locale) {
+                        self.locale = Some(locale);
+                    }
+                }
+
+                self.navigator
+                    .change_position(position.slide, position.step);
+            }
+            Message::TouchStart(touches, time) => {
+                self.touch_tracker.start(&touches, time);
+                return self.show_controls();
+            }
+            Message::TouchMove(touches) => {
+                let panning = self.slide_size.is_zoomed();
+                let gesture = self.touch_tracker.move_to(&touches, panning);
+
+                return self.handle_gesture(gesture);
+            }
+            Message::TouchEnd(remaining_touches, time) => {
+                let panning = self.slide_size.is_zoomed();
+                let gesture =
+                    self.touch_tracker.end(remaining_touches, time, panning);
+
+                return self.handle_gesture(gesture);
+            }
+            Message::Annotate(annotation) => {
+                if let Some(sender) = &self.annotation_sender {
+                    sender.emit(annotation.clone());
+                }
 
-                if self.current_slide != slide {
-                    self.current_slide = slide.into();
+                Rc::make_mut(&mut self.annotations).apply(annotation);
+                return true;
+            }
+            Message::AnnotationReceived(annotation) => {
+                Rc::make_mut(&mut self.annotations).apply(annotation);
+                return true;
+            }
+            Message::AnnotationSenderReady(sender) => {
+                self.annotation_sender = Some(sender);
+                return false;
+            }
+            Message::Vote(poll, option) => {
+                self.send(ClientMessage::Vote { poll, option });
+                return false;
+            }
+            Message::PollResultsReceived(results) => {
+                Rc::make_mut(&mut self.poll_results)
+                    .insert(results.poll, results);
+                return true;
+            }
+            Message::React(reaction) => {
+                if !self.presenting {
+                    self.send(ClientMessage::React { reaction });
                 }
 
-                self.current_step = step.into();
+                return false;
+            }
+            Message::ReactionsReceived(reactions) => {
+                let burst = self.reactions.add(&reactions);
+
+                self.reaction_timeouts.push_back(TimeoutService::spawn(
+                    REACTION_DURATION,
+                    self.component_link
+                        .callback(move |_| Message::ReactionsExpired(burst)),
+                ));
+
+                return true;
+            }
+            Message::ReactionsExpired(burst) => {
+                // Every burst lasts as long, so they expire in order.
+                self.reaction_timeouts.pop_front();
+                self.reactions.expire(burst);
+                return true;
+            }
+            Message::MessageSenderReady(sender) => {
+                self.message_sender = Some(sender);
+                return false;
             }
             Message::TogglePresent => self.presenting = !self.presenting,
+            Message::PresentingRefused => self.presenting = false,
             Message::Resize(dimensions) => self.resize(dimensions),
             Message::Ignore => return false,
         }
+
+        if self.navigator.slide() != previous_slide {
+            Rc::make_mut(&mut self.annotations).clear();
+        }
+
+        self.update_location(new_history_entry);
+
         true
     }
 
     fn change(&mut self, properties: Self::Properties) -> ShouldRender {
         self.locales = properties.locales;
+        self.base_key_bindings = properties.key_bindings;
+        self.base_slide_format = properties.slide_format;
+        self.base_slide_fit = properties.slide_fit;
+        self.apply_key_bindings();
+        self.apply_slide_format();
 
         if self.locale.is_none() && !self.locales.is_empty() {
             self.locale = self.locales.first().cloned();
@@ -192,16 +826,49 @@ impl Component for Presentrs {
             }
         }
 
+        self.update_location(false);
+
         true
     }
 
+    fn rendered(&mut self, _first_render: bool) {
+        if self.refocus {
+            self.refocus = false;
+
+            if let Some(root) = self.root.cast::<HtmlElement>() {
+                let _ = root.focus();
+            }
+        }
+    }
+
+    #[allow(clippy::unnecessary_operation)]
     fn view(&self) -> Html {
-        let key_down_callback = self.component_link.callback(Self::on_key_down);
-        let slide_loaded_callback =
-            self.component_link
-                .callback(|(slide_index, slide_step_count)| {
-                    Message::SlideLoaded(slide_index, slide_step_count)
-                });
+        let key_bindings = self.key_bindings.clone();
+        let key_down_callback = self
+            .component_link
+            .callback(move |event| Self::on_key_down(&key_bindings, event));
+        let cursor = if self.fullscreen && !self.controls_visible {
+            "none"
+        } else {
+            "auto"
+        };
+        let mouse_move_callback =
+            self.component_link.callback(|_| Message::ShowControls);
+        let touch_start_callback =
+            self.component_link.callback(Self::on_touch_start);
+        let touch_move_callback =
+            self.component_link.callback(Self::on_touch_move);
+        let touch_end_callback =
+            self.component_link.callback(Self::on_touch_end);
+        let touch_cancel_callback =
+            self.component_link.callback(Self::on_touch_end);
+        let slide_loaded_callback = self.component_link.callback(
+            |(slide_index, slide_step_count, summary)| {
+                Message::SlideLoaded(slide_index, slide_step_count, summary)
+            },
+        );
+        let manifest_loaded_callback =
+            self.component_link.callback(Message::ManifestLoaded);
         let previous_slide_callback =
             self.component_link.callback(|_| Message::PreviousSlide);
         let previous_step_callback =
@@ -213,29 +880,60 @@ impl Component for Presentrs {
         let update_position_callback = self
             .component_link
             .callback(|(slide, step)| Message::ChangePosition { slide, step });
+        let annotate_callback = self
+            .presenting
+            .then(|| self.component_link.callback(Message::Annotate));
+        let annotation_received_callback =
+            self.component_link.callback(Message::AnnotationReceived);
+        let annotation_sender_callback =
+            self.component_link.callback(Message::AnnotationSenderReady);
+        let vote_callback = (!self.presenting).then(|| {
+            self.component_link
+                .callback(|(poll, option)| Message::Vote(poll, option))
+        });
+        let poll_results_callback =
+            self.component_link.callback(Message::PollResultsReceived);
+        let reactions_callback =
+            self.component_link.callback(Message::ReactionsReceived);
+        let message_sender_callback =
+            self.component_link.callback(Message::MessageSenderReady);
+        let presenting_refused_callback =
+            self.component_link.callback(|_| Message::PresentingRefused);
 
         html! {
             <div
+                ref = self.root.clone()
                 tabindex = 0
                 onkeydown = key_down_callback
-                style = {"
+                onmousemove = mouse_move_callback
+                ontouchstart = touch_start_callback
+                ontouchmove = touch_move_callback
+                ontouchend = touch_end_callback
+                ontouchcancel = touch_cancel_callback
+                style = {format!("
                     position: absolute;
                     left: 0;
                     right: 0;
                     top: 0;
                     bottom: 0;
-                "}
+                    cursor: {};
+                ", cursor)}
                 >
                 <Slides
                     locale = self.locale.clone()
-                    current_slide = self.current_slide
-                    current_step = self.current_step
+                    current_slide = self.navigator.slide()
+                    current_step = self.navigator.step()
                     size = self.slide_size
                     on_slide_loaded = slide_loaded_callback
+                    on_manifest_loaded = manifest_loaded_callback
+                    annotations = self.annotations.clone()
+                    on_annotate = annotate_callback
+                    poll_results = self.poll_results.clone()
+                    on_vote = vote_callback
                     />
                 <Notes
-                    current_slide = self.current_slide
-                    current_step = self.current_step
+                    current_slide = self.navigator.slide()
+                    current_step = self.navigator.step()
                     enabled = self.show_notes
                     />
                 <Navigation
@@ -243,11 +941,24 @@ impl Component for Presentrs {
                     on_previous_step = previous_step_callback
                     on_next_step = next_step_callback
                     on_next_slide = next_slide_callback
-                    on_update_position = update_position_callback
+                    on_update_position = update_position_callback.clone()
+                    on_annotation = annotation_received_callback
+                    on_annotation_sender = annotation_sender_callback
+                    on_poll_results = poll_results_callback
+                    on_reactions = reactions_callback
+                    on_message_sender = message_sender_callback
+                    on_presenting_refused = presenting_refused_callback
                     presenting = self.presenting
-                    current_slide = self.current_slide
-                    current_step = self.current_step
+                    current_slide = self.navigator.slide()
+                    current_step = self.navigator.step()
+                    visible = self.controls_visible
                     />
+                { self.view_typed_slide() }
+                { self.view_search() }
+                { self.view_help() }
+                { self.view_playback(update_position_callback) }
+                { self.reactions.view() }
+                { self.view_blank() }
             </div>
         }
     }
@@ -256,6 +967,9 @@ impl Component for Presentrs {
 #[derive(Clone, Debug, Default, Properties)]
 pub struct Properties {
     locales: Vec<String>,
+    key_bindings: KeyBindings,
+    slide_format: SlideFormat,
+    slide_fit: SlideFit,
 }
 
 impl Properties {
@@ -276,4 +990,67 @@ impl Properties {
         self.locales = locales.into_iter().map(|item| item.into()).collect();
         self
     }
+
+    pub fn with_key_bindings(mut self, key_bindings: KeyBindings) -> Self {
+        self.key_bindings = key_bindings;
+        self
+    }
+
+    pub fn set_key_bindings(&mut self, key_bindings: KeyBindings) -> &mut Self {
+        self.key_bindings = key_bindings;
+        self
+    }
+
+    pub fn with_slide_format(
+        mut self,
+        format: SlideFormat,
+        fit: SlideFit,
+    ) -> Self {
+        self.slide_format = format;
+        self.slide_fit = fit;
+        self
+    }
+
+    pub fn set_slide_format(
+        &mut self,
+        format: SlideFormat,
+        fit: SlideFit,
+    ) -> &mut Self {
+        self.slide_format = format;
+        self.slide_fit = fit;
+        self
+    }
+}
+
+fn key_label(key: &str) -> &str {
+    match key {
+        " " => "Space",
+        "ArrowLeft" => "←",
+        "ArrowRight" => "→",
+        "ArrowUp" => "↑",
+        "ArrowDown" => "↓",
+        key => key,
+    }
+}
+
+impl From<KeyAction> for Message {
+    fn from(action: KeyAction) -> Self {
+        match action {
+            KeyAction::PreviousStep => Message::PreviousStep,
+            KeyAction::NextStep => Message::NextStep,
+            KeyAction::PreviousSlide => Message::PreviousSlide,
+            KeyAction::NextSlide => Message::NextSlide,
+            KeyAction::FirstSlide => Message::FirstSlide,
+            KeyAction::LastSlide => Message::LastSlide,
+            KeyAction::JumpToTypedSlide => Message::JumpToTypedSlide,
+            KeyAction::Search => Message::OpenSearch,
+            KeyAction::ToggleNotes => Message::ToggleNotes,
+            KeyAction::TogglePresent => Message::TogglePresent,
+            KeyAction::BlankScreen => Message::ToggleBlank,
+            KeyAction::ToggleFullscreen => Message::ToggleFullscreen,
+            KeyAction::Cancel => Message::CancelInput,
+            KeyAction::ToggleHelp => Message::ToggleHelp,
+            KeyAction::React => Message::React(Reaction::Applause),
+        }
+    }
 }
@@ -1,8 +1,11 @@
 use {
     super::{
+        base_url,
         navigation_button::{Direction, NavigationButton, Target},
         slide_sync::SlideSync,
     },
+    crate::sync_message::{Annotation, ClientMessage, PollResults, Reaction},
+    std::collections::BTreeMap,
     yew::prelude::*,
 };
 
@@ -13,9 +16,16 @@ pub struct Navigation {
     on_next_step: Option<Callback<()>>,
     on_next_slide: Option<Callback<()>>,
     on_update_position: Callback<(u16, u16)>,
+    on_annotation: Callback<Annotation>,
+    on_annotation_sender: Callback<Callback<Annotation>>,
+    on_poll_results: Callback<PollResults>,
+    on_reactions: Callback<BTreeMap<Reaction, u32>>,
+    on_message_sender: Callback<Callback<ClientMessage>>,
+    on_presenting_refused: Callback<()>,
     presenting: bool,
     current_slide: usize,
     current_step: usize,
+    visible: bool,
 }
 
 impl Component for Navigation {
@@ -23,19 +33,23 @@ impl Component for Navigation {
     type Properties = Properties;
 
     fn create(properties: Self::Properties, _: ComponentLink<Self>) -> Self {
-        let window = web_sys::window().expect("Failed to access window");
-        let host = window.location().host().expect("Invalid host location");
-
         Navigation {
-            sync_url: format!("ws://{}/sync", host),
+            sync_url: base_url::resolve_web_socket("sync"),
             on_previous_slide: properties.on_previous_slide,
             on_previous_step: properties.on_previous_step,
             on_next_step: properties.on_next_step,
             on_next_slide: properties.on_next_slide,
             on_update_position: properties.on_update_position,
+            on_annotation: properties.on_annotation,
+            on_annotation_sender: properties.on_annotation_sender,
+            on_poll_results: properties.on_poll_results,
+            on_reactions: properties.on_reactions,
+            on_message_sender: properties.on_message_sender,
+            on_presenting_refused: properties.on_presenting_refused,
             presenting: properties.presenting,
             current_slide: properties.current_slide,
             current_step: properties.current_step,
+            visible: properties.visible,
         }
     }
 
@@ -49,21 +63,38 @@ impl Component for Navigation {
         self.on_next_step = properties.on_next_step;
         self.on_next_slide = properties.on_next_slide;
         self.on_update_position = properties.on_update_position;
+        self.on_annotation = properties.on_annotation;
+        self.on_annotation_sender = properties.on_annotation_sender;
+        self.on_poll_results = properties.on_poll_results;
+        self.on_reactions = properties.on_reactions;
+        self.on_message_sender = properties.on_message_sender;
+        self.on_presenting_refused = properties.on_presenting_refused;
         self.presenting = properties.presenting;
         self.current_slide = properties.current_slide;
         self.current_step = properties.current_step;
+        self.visible = properties.visible;
 
         true
     }
 
+    // Setting component properties in `html!` trips clippy.
+    #[allow(clippy::unnecessary_operation)]
     fn view(&self) -> Html {
+        let visibility = if self.visible {
+            "opacity: 1;"
+        } else {
+            "opacity: 0; pointer-events: none;"
+        };
+
         html! {
-            <div style="
+            <div style=format!("\
                 position: absolute;\
                 left: 0;\
                 right: 0;\
                 bottom: 0;\
-            ">
+                transition: opacity 0.5s;\
+                {}\
+            ", visibility)>
                 <div style="\
                     margin-left: auto;\
                     margin-right: auto;\
@@ -85,6 +116,12 @@ impl Component for Navigation {
                         current_slide = self.current_slide
                         current_step = self.current_step
                         on_update_position = &self.on_update_position
+                        on_annotation = &self.on_annotation
+                        on_annotation_sender = &self.on_annotation_sender
+                        on_poll_results = &self.on_poll_results
+                        on_reactions = &self.on_reactions
+                        on_message_sender = &self.on_message_sender
+                        on_presenting_refused = &self.on_presenting_refused
                         />
                     <NavigationButton
                         direction=Direction::Forward
@@ -109,7 +146,15 @@ pub struct Properties {
     pub on_next_step: Option<Callback<()>>,
     pub on_next_slide: Option<Callback<()>>,
     pub on_update_position: Callback<(u16, u16)>,
+    pub on_annotation: Callback<Annotation>,
+    pub on_annotation_sender: Callback<Callback<Annotation>>,
+    pub on_poll_results: Callback<PollResults>,
+    pub on_reactions: Callback<BTreeMap<Reaction, u32>>,
+    pub on_message_sender: Callback<Callback<ClientMessage>>,
+    pub on_presenting_refused: Callback<()>,
     pub presenting: bool,
     pub current_slide: usize,
     pub current_step: usize,
+    #[prop_or(true)]
+    pub visible: bool,
 }
@@ -85,6 +85,17 @@ impl Direction {
     }
 }
 
+static SLIDE_STYLE: Lazy<&'static str> = Lazy::new(|| {
+    let (slide_style_end, _) = Target::STEP_STYLE
+        .chars()
+        .enumerate()
+        .filter(|(_, character)| *character == ';')
+        .nth(2)
+        .expect("STEP_STYLE couldn't be mapped into SLIDE_STYLE");
+
+    &Target::STEP_STYLE[..slide_style_end]
+});
+
 impl Target {
     const STEP_STYLE: &'static str = "\
         fill: none;\
@@ -95,21 +106,10 @@ impl Target {
         stroke-linejoin: round;\
     ";
 
-    const SLIDE_STYLE: Lazy<&'static str> = Lazy::new(|| {
-        let (slide_style_end, _) = Target::STEP_STYLE
-            .chars()
-            .enumerate()
-            .filter(|(_, character)| *character == ';')
-            .nth(2)
-            .expect("STEP_STYLE couldn't be mapped into SLIDE_STYLE");
-
-        &Target::STEP_STYLE[..slide_style_end]
-    });
-
     fn style(&self) -> &'static str {
         match self {
             Target::Step => Self::STEP_STYLE,
-            Target::Slide => *Self::SLIDE_STYLE,
+            Target::Slide => *SLIDE_STYLE,
         }
     }
 }
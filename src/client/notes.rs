@@ -1,3 +1,4 @@
+use super::base_url;
 use yew::format::{Nothing, Text};
 use yew::prelude::*;
 use yew::services::fetch::{FetchService, FetchTask, Request, Response};
@@ -15,7 +16,8 @@ impl Component for Notes {
     type Message = Message;
 
     fn create(properties: Self::Properties, link: ComponentLink<Self>) -> Self {
-        let get_notes_request = Request::get("/notes.html").body(Nothing);
+        let get_notes_request =
+            Request::get(base_url::resolve("notes.html")).body(Nothing);
         let status;
 
         if let Ok(request) = get_notes_request {
@@ -112,7 +114,7 @@ impl Component for Notes {
 }
 
 pub enum Status {
-    Loading(FetchTask),
+    Loading(#[allow(dead_code)] FetchTask),
     Ready(String),
     Error,
 }
@@ -0,0 +1,318 @@
+use {
+    super::base_url,
+    crate::{config::PlaybackConfig, sync_message::SyncPosition, Timeline},
+    std::time::Duration,
+    wasm_bindgen::JsCast,
+    web_sys::HtmlMediaElement,
+    yew::{
+        format::{Nothing, Text},
+        prelude::*,
+        services::{
+            fetch::{FetchService, FetchTask, Request, Response},
+            interval::{IntervalService, IntervalTask},
+        },
+    },
+};
+
+/// How often the position is updated while replaying.
+const TICK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Replays a recorded timeline, either on its own clock or following the
+/// current time of a `<video>` or `<audio>` element.
+pub struct Playback {
+    component_link: ComponentLink<Self>,
+    config: PlaybackConfig,
+    timeline: Option<Timeline>,
+    clock: Clock,
+    last_position: Option<SyncPosition>,
+    on_update_position: Callback<(u16, u16)>,
+    visible: bool,
+    timeline_task: Option<FetchTask>,
+    _ticker: IntervalTask,
+}
+
+/// The playback clock used when there is no media element to follow.
+#[derive(Clone, Copy, Debug, Default)]
+struct Clock {
+    /// The time when the clock was last paused or seeked, in seconds.
+    offset: f64,
+    /// When the clock started running, in milliseconds since the Unix epoch.
+    running_since: Option<f64>,
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Properties {
+    pub config: PlaybackConfig,
+    pub on_update_position: Callback<(u16, u16)>,
+    #[prop_or(true)]
+    pub visible: bool,
+}
+
+pub enum Message {
+    TimelineLoaded(Option<Timeline>),
+    Tick,
+    TogglePlay,
+    Seek(f64),
+    Ignore,
+}
+
+impl Clock {
+    fn time(&self) -> f64 {
+        match self.running_since {
+            Some(since) => self.offset + (js_sys::Date::now() - since) / 1000.0,
+            None => self.offset,
+        }
+    }
+
+    fn play(&mut self) {
+        self.running_since.get_or_insert_with(js_sys::Date::now);
+    }
+
+    fn pause(&mut self) {
+        self.offset = self.time();
+        self.running_since = None;
+    }
+
+    fn seek(&mut self, time: f64) {
+        self.offset = time;
+
+        if self.running_since.is_some() {
+            self.running_since = Some(js_sys::Date::now());
+        }
+    }
+}
+
+impl Playback {
+    fn fetch_timeline(&mut self) {
+        let request = Request::get(base_url::resolve(&self.config.timeline))
+            .body(Nothing);
+
+        if let Ok(request) = request {
+            let fetch_task = FetchService::fetch(
+                request,
+                self.component_link.callback(|response: Response<Text>| {
+                    let (meta, body) = response.into_parts();
+
+                    let timeline = body
+                        .ok()
+                        .filter(|_| meta.status.is_success())
+                        .and_then(|body| serde_json::from_str(&body).ok());
+
+                    Message::TimelineLoaded(timeline)
+                }),
+            );
+
+            self.timeline_task = fetch_task.ok();
+        }
+    }
+
+    /// The media element the playback follows, if one is configured and
+    /// it's on the page.
+    fn media(&self) -> Option<HtmlMediaElement> {
+        let selector = self.config.media.as_ref()?;
+
+        web_sys::window()?
+            .document()?
+            .query_selector(selector)
+            .ok()??
+            .dyn_into()
+            .ok()
+    }
+
+    fn time(&self) -> f64 {
+        match self.media() {
+            Some(media) => media.current_time(),
+            None => self.clock.time(),
+        }
+    }
+
+    fn is_playing(&self) -> bool {
+        match self.media() {
+            Some(media) => !media.paused(),
+            None => self.clock.running_since.is_some(),
+        }
+    }
+
+    fn tick(&mut self) -> ShouldRender {
+        let timeline = match &self.timeline {
+            Some(timeline) => timeline,
+            None => return false,
+        };
+
+        if self.media().is_none() && self.clock.time() >= timeline.duration() {
+            self.clock.pause();
+            self.clock.offset = timeline.duration();
+        }
+
+        let position = timeline.position_at(self.time());
+
+        if position != self.last_position {
+            self.last_position = position;
+
+            if let Some(SyncPosition { slide, step }) = position {
+                self.on_update_position.emit((slide, step));
+            }
+        }
+
+        true
+    }
+
+    fn toggle_play(&mut self) {
+        match self.media() {
+            Some(media) if media.paused() => drop(media.play()),
+            Some(media) => drop(media.pause()),
+            None if self.clock.running_since.is_some() => self.clock.pause(),
+            None => {
+                let duration =
+                    self.timeline.as_ref().map_or(0.0, Timeline::duration);
+
+                if self.clock.time() >= duration {
+                    self.clock.seek(0.0);
+                }
+
+                self.clock.play();
+            }
+        }
+    }
+
+    fn seek(&mut self, time: f64) {
+        match self.media() {
+            Some(media) => media.set_current_time(time),
+            None => self.clock.seek(time),
+        }
+    }
+
+    fn format_time(seconds: f64) -> String {
+        let seconds = seconds.max(0.0) as u64;
+
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+impl Component for Playback {
+    type Message = Message;
+    type Properties = Properties;
+
+    fn create(
+        properties: Self::Properties,
+        component_link: ComponentLink<Self>,
+    ) -> Self {
+        let ticker = IntervalService::spawn(
+            TICK_INTERVAL,
+            component_link.callback(|_| Message::Tick),
+        );
+        let mut this = Playback {
+            component_link,
+            config: properties.config,
+            timeline: None,
+            clock: Clock::default(),
+            last_position: None,
+            on_update_position: properties.on_update_position,
+            visible: properties.visible,
+            timeline_task: None,
+            _ticker: ticker,
+        };
+
+        this.fetch_timeline();
+        this
+    }
+
+    fn update(&mut self, message: Self::Message) -> ShouldRender {
+        match message {
+            Message::TimelineLoaded(timeline) => {
+                self.timeline_task = None;
+                self.timeline = timeline;
+                self.tick()
+            }
+            Message::Tick => self.tick(),
+            Message::TogglePlay => {
+                self.toggle_play();
+                self.tick()
+            }
+            Message::Seek(time) => {
+                self.seek(time);
+                self.tick()
+            }
+            Message::Ignore => false,
+        }
+    }
+
+    fn change(&mut self, properties: Self::Properties) -> ShouldRender {
+        let config_changed = self.config != properties.config;
+
+        self.config = properties.config;
+        self.on_update_position = properties.on_update_position;
+        self.visible = properties.visible;
+
+        if config_changed {
+            self.timeline = None;
+            self.clock = Clock::default();
+            self.last_position = None;
+            self.fetch_timeline();
+        }
+
+        true
+    }
+
+    fn view(&self) -> Html {
+        let timeline = match &self.timeline {
+            Some(timeline) => timeline,
+            None => return html! {},
+        };
+
+        let visibility = if self.visible {
+            "opacity: 1;"
+        } else {
+            "opacity: 0; pointer-events: none;"
+        };
+        let time = self.time();
+        let duration = timeline.duration();
+        let toggle_play = self.component_link.callback(|_| Message::TogglePlay);
+        let seek = self.component_link.callback(|input: InputData| {
+            input.value.parse().map_or(Message::Ignore, Message::Seek)
+        });
+        // Keeps the presentation's key bindings from reacting to the slider.
+        let stop_keys = Callback::from(|event: KeyboardEvent| {
+            event.stop_propagation();
+        });
+
+        html! {
+            <div style=format!("\
+                position: absolute;\
+                top: 10px;\
+                left: 50%;\
+                transform: translateX(-50%);\
+                display: flex;\
+                align-items: center;\
+                padding: 4px 8px;\
+                background: rgba(255, 255, 255, 0.9);\
+                border: 1px solid gray;\
+                transition: opacity 0.5s;\
+                {}\
+            ", visibility)>
+                <button onclick = toggle_play>
+                    { if self.is_playing() { "Pause" } else { "Play" } }
+                </button>
+                <input
+                    type = "range"
+                    min = 0
+                    max = duration.to_string()
+                    step = 0.1
+                    value = time.min(duration).to_string()
+                    oninput = seek
+                    onkeydown = stop_keys
+                    style = "width: 300px; margin: 0 8px;"
+                    />
+                <span style="font-family: monospace;">
+                    {
+                        format!(
+                            "{} / {}",
+                            Self::format_time(time),
+                            Self::format_time(duration),
+                        )
+                    }
+                </span>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use {crate::sync_message::Question, yew::prelude::*};
+
+/// The Q&A queue, where the audience asks questions and presenters upvote
+/// or dismiss them.
+pub struct QuestionPanel {
+    component_link: ComponentLink<Self>,
+    properties: Properties,
+    draft: String,
+    open: bool,
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Properties {
+    pub presenting: bool,
+    pub questions: Vec<Question>,
+    pub on_ask: Callback<String>,
+    pub on_upvote: Callback<u32>,
+    pub on_dismiss: Callback<u32>,
+}
+
+pub enum Message {
+    Toggle,
+    UpdateDraft(String),
+    Ask,
+    Ignore,
+}
+
+impl QuestionPanel {
+    fn on_key_down(event: KeyboardEvent) -> Message {
+        // Keeps the presentation's key bindings from reacting to typing.
+        event.stop_propagation();
+
+        match event.key().as_str() {
+            "Enter" => Message::Ask,
+            "Escape" => Message::Toggle,
+            _ => Message::Ignore,
+        }
+    }
+
+    fn view_questions(&self) -> Html {
+        if self.properties.questions.is_empty() {
+            return html! { <p>{ "No questions yet" }</p> };
+        }
+
+        html! {
+            <ul style="list-style: none; margin: 0; padding: 0;">
+                { for self.properties.questions.iter().map(|question| {
+                    self.view_question(question)
+                }) }
+            </ul>
+        }
+    }
+
+    fn view_question(&self, question: &Question) -> Html {
+        let id = question.id;
+        let upvote = self.properties.on_upvote.reform(move |_| id);
+        let dismiss = self.properties.on_dismiss.reform(move |_| id);
+
+        html! {
+            <li style="padding: 4px 0; border-bottom: 1px solid lightgray;">
+                <button onclick = upvote title = "Upvote">
+                    { format!("▲ {}", question.votes) }
+                </button>
+                <button onclick = dismiss title = "Dismiss">{ "✕" }</button>
+                <span style="margin-left: 5px;">{ &question.text }</span>
+            </li>
+        }
+    }
+
+    fn view_ask(&self) -> Html {
+        let key_down_callback = self.component_link.callback(Self::on_key_down);
+        let input_callback = self
+            .component_link
+            .callback(|input: InputData| Message::UpdateDraft(input.value));
+        let ask_callback = self.component_link.callback(|_| Message::Ask);
+
+        html! {
+            <div style="display: flex;">
+                <input
+                    type = "text"
+                    placeholder = "Ask a question"
+                    value = self.draft.clone()
+                    oninput = input_callback
+                    onkeydown = key_down_callback
+                    style = "flex: 1; padding: 4px;"
+                    />
+                <button onclick = ask_callback>{ "Ask" }</button>
+            </div>
+        }
+    }
+}
+
+impl Component for QuestionPanel {
+    type Message = Message;
+    type Properties = Properties;
+
+    fn create(
+        properties: Self::Properties,
+        component_link: ComponentLink<Self>,
+    ) -> Self {
+        QuestionPanel {
+            component_link,
+            properties,
+            draft: String::new(),
+            open: false,
+        }
+    }
+
+    fn update(&mut self, message: Self::Message) -> ShouldRender {
+        match message {
+            Message::Toggle => self.open = !self.open,
+            Message::UpdateDraft(draft) => self.draft = draft,
+            Message::Ask => {
+                let question = self.draft.trim();
+
+                if !question.is_empty() {
+                    self.properties.on_ask.emit(question.to_owned());
+                    self.draft.clear();
+                }
+            }
+            Message::Ignore => return false,
+        }
+
+        true
+    }
+
+    fn change(&mut self, properties: Self::Properties) -> ShouldRender {
+        if self.properties != properties {
+            self.properties = properties;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let toggle_callback = self.component_link.callback(|_| Message::Toggle);
+        let label = if self.properties.presenting {
+            format!("Q&A ({})", self.properties.questions.len())
+        } else {
+            "Q&A".to_owned()
+        };
+
+        html! {
+            <span style="position: relative; margin-left: 5px; vertical-align: top">
+                <button onclick = toggle_callback>{ label }</button>
+                {
+                    if self.open {
+                        html! {
+                            <div style="\
+                                position: absolute;\
+                                bottom: 100%;\
+                                left: 0;\
+                                width: 300px;\
+                                max-height: 300px;\
+                                overflow-y: auto;\
+                                padding: 8px;\
+                                background: white;\
+                                border: 1px solid gray;\
+                                box-shadow: 0 2px 8px rgba(0, 0, 0, 0.3);\
+                            ">
+                                {
+                                    if self.properties.presenting {
+                                        self.view_questions()
+                                    } else {
+                                        self.view_ask()
+                                    }
+                                }
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+            </span>
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use {
+    super::styles,
+    crate::sync_message::Reaction,
+    std::{collections::BTreeMap, time::Duration},
+    yew::prelude::*,
+};
+
+/// How long a reaction floats over the slides.
+pub const REACTION_DURATION: Duration = Duration::from_secs(3);
+
+/// How many emoji are shown for each kind of reaction in a burst, so that a
+/// large audience doesn't flood the presenter's screen.
+const MAX_EMOJI_PER_BURST: u32 = 8;
+
+const REACTIONS_CSS: &str = "
+    .floating-reaction {
+        position: absolute;
+        bottom: 0;
+        font-size: 32px;
+        pointer-events: none;
+        animation: presentrs-float-up 3s ease-out both;
+    }
+
+    @keyframes presentrs-float-up {
+        from { transform: translateY(0); opacity: 1; }
+        to { transform: translateY(-60vh); opacity: 0; }
+    }
+";
+
+/// The audience's reactions floating up the presenter's screen.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FloatingReactions {
+    emoji: Vec<FloatingEmoji>,
+    next_burst: u64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct FloatingEmoji {
+    burst: u64,
+    reaction: Reaction,
+    /// The horizontal position, from zero to one across the screen.
+    left: f64,
+    /// How long to wait before floating up, in seconds.
+    delay: f64,
+}
+
+impl FloatingReactions {
+    /// Shows a burst of reactions, returning its ID so that it can be
+    /// removed with `expire` after `REACTION_DURATION`.
+    pub fn add(&mut self, reactions: &BTreeMap<Reaction, u32>) -> u64 {
+        let burst = self.next_burst;
+
+        styles::inject("presentrs-reactions", REACTIONS_CSS);
+        self.next_burst += 1;
+
+        for (&reaction, &count) in reactions {
+            for _ in 0..count.min(MAX_EMOJI_PER_BURST) {
+                self.emoji.push(FloatingEmoji {
+                    burst,
+                    reaction,
+                    left: 0.05 + js_sys::Math::random() * 0.9,
+                    delay: js_sys::Math::random() * 0.5,
+                });
+            }
+        }
+
+        burst
+    }
+
+    pub fn expire(&mut self, burst: u64) {
+        self.emoji.retain(|emoji| emoji.burst != burst);
+    }
+
+    pub fn view(&self) -> Html {
+        if self.emoji.is_empty() {
+            return html! {};
+        }
+
+        html! {
+            <div style="\
+                position: absolute;\
+                top: 0;\
+                left: 0;\
+                right: 0;\
+                bottom: 0;\
+                overflow: hidden;\
+                pointer-events: none;\
+            ">
+                { for self.emoji.iter().map(|emoji| html! {
+                    <span
+                        class = "floating-reaction"
+                        style = format!(
+                            "left: {}%; animation-delay: {}s;",
+                            emoji.left * 100.0,
+                            emoji.delay,
+                        )
+                        >
+                        { emoji.reaction.emoji() }
+                    </span>
+                }) }
+            </div>
+        }
+    }
+}
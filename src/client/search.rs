@@ -0,0 +1,307 @@
+use {
+    crate::manifest::SlideSummary, web_sys::HtmlInputElement, yew::prelude::*,
+};
+
+const MAX_RESULTS: usize = 10;
+
+pub struct SearchPalette {
+    component_link: ComponentLink<Self>,
+    entries: Vec<(usize, SlideSummary)>,
+    on_select: Callback<usize>,
+    on_close: Callback<()>,
+    query: String,
+    results: Vec<usize>,
+    selected: usize,
+    input: NodeRef,
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Properties {
+    pub entries: Vec<(usize, SlideSummary)>,
+    pub on_select: Callback<usize>,
+    pub on_close: Callback<()>,
+}
+
+pub enum Message {
+    UpdateQuery(String),
+    SelectPrevious,
+    SelectNext,
+    Confirm,
+    Close,
+    Ignore,
+}
+
+impl SearchPalette {
+    fn search(&mut self) {
+        let mut scored: Vec<_> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(position, (_, summary))| {
+                Self::score(&self.query, summary).map(|score| (score, position))
+            })
+            .collect();
+
+        scored.sort_by(|(score_a, position_a), (score_b, position_b)| {
+            score_b.cmp(score_a).then(position_a.cmp(position_b))
+        });
+
+        self.results = scored
+            .into_iter()
+            .map(|(_, position)| position)
+            .take(MAX_RESULTS)
+            .collect();
+        self.selected = 0;
+    }
+
+    fn score(query: &str, summary: &SlideSummary) -> Option<usize> {
+        if query.trim().is_empty() {
+            return Some(0);
+        }
+
+        let title_score =
+            fuzzy_score(query, &summary.title).map(|score| score * 2);
+        let text_score = fuzzy_score(query, &summary.text);
+
+        title_score.max(text_score)
+    }
+
+    fn view_result(&self, index: usize, position: usize) -> Html {
+        let (slide, summary) = &self.entries[position];
+        let slide = *slide;
+        let on_click = self.on_select.reform(move |_: MouseEvent| slide);
+        let background = if index == self.selected {
+            "background: lightgray;"
+        } else {
+            ""
+        };
+        let title = if summary.title.is_empty() {
+            format!("Slide {}", slide)
+        } else {
+            format!("{}. {}", slide, summary.title)
+        };
+
+        html! {
+            <li
+                onclick = on_click
+                style = format!("padding: 4px 8px; cursor: pointer; {}", background)
+                >
+                { title }
+            </li>
+        }
+    }
+
+    fn on_key_down(event: KeyboardEvent) -> Message {
+        event.stop_propagation();
+
+        let message = match event.key().as_str() {
+            "ArrowUp" => Message::SelectPrevious,
+            "ArrowDown" => Message::SelectNext,
+            "Enter" => Message::Confirm,
+            "Escape" => Message::Close,
+            _ => return Message::Ignore,
+        };
+
+        event.prevent_default();
+
+        message
+    }
+}
+
+impl Component for SearchPalette {
+    type Message = Message;
+    type Properties = Properties;
+
+    fn create(
+        properties: Self::Properties,
+        component_link: ComponentLink<Self>,
+    ) -> Self {
+        let mut this = SearchPalette {
+            component_link,
+            entries: properties.entries,
+            on_select: properties.on_select,
+            on_close: properties.on_close,
+            query: String::new(),
+            results: Vec::new(),
+            selected: 0,
+            input: NodeRef::default(),
+        };
+
+        this.search();
+        this
+    }
+
+    fn update(&mut self, message: Self::Message) -> ShouldRender {
+        match message {
+            Message::UpdateQuery(query) => {
+                self.query = query;
+                self.search();
+            }
+            Message::SelectPrevious => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            Message::SelectNext => {
+                if self.selected + 1 < self.results.len() {
+                    self.selected += 1;
+                }
+            }
+            Message::Confirm => {
+                let chosen = self
+                    .results
+                    .get(self.selected)
+                    .and_then(|&position| self.entries.get(position));
+
+                match chosen {
+                    Some((slide, _)) => self.on_select.emit(*slide),
+                    None => self.on_close.emit(()),
+                }
+
+                return false;
+            }
+            Message::Close => {
+                self.on_close.emit(());
+                return false;
+            }
+            Message::Ignore => return false,
+        }
+
+        true
+    }
+
+    fn change(&mut self, properties: Self::Properties) -> ShouldRender {
+        self.on_select = properties.on_select;
+        self.on_close = properties.on_close;
+
+        if self.entries != properties.entries {
+            self.entries = properties.entries;
+            self.search();
+        }
+
+        true
+    }
+
+    fn rendered(&mut self, first_render: bool) {
+        if first_render {
+            if let Some(input) = self.input.cast::<HtmlInputElement>() {
+                let _ = input.focus();
+            }
+        }
+    }
+
+    fn view(&self) -> Html {
+        let key_down_callback = self.component_link.callback(Self::on_key_down);
+        let input_callback = self
+            .component_link
+            .callback(|input: InputData| Message::UpdateQuery(input.value));
+
+        html! {
+            <div style="
+                position: absolute;\
+                top: 10%;\
+                left: 50%;\
+                transform: translateX(-50%);\
+                width: 400px;\
+                background: white;\
+                border: 1px solid gray;\
+                box-shadow: 0 2px 8px rgba(0, 0, 0, 0.3);\
+                z-index: 10;\
+            ">
+                <input
+                    ref = self.input.clone()
+                    type = "text"
+                    placeholder = "Search slides"
+                    value = self.query.clone()
+                    oninput = input_callback
+                    onkeydown = key_down_callback
+                    style = "width: 100%; box-sizing: border-box; padding: 8px;"
+                    />
+                <ul style="list-style: none; margin: 0; padding: 0;">
+                    { for self.results.iter().enumerate().map(|(index, &position)| {
+                        self.view_result(index, position)
+                    }) }
+                </ul>
+            </div>
+        }
+    }
+}
+
+pub fn fuzzy_score(query: &str, text: &str) -> Option<usize> {
+    let mut query_chars = query
+        .chars()
+        .filter(|character| !character.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .peekable();
+    let mut score = 0;
+    let mut consecutive = 0;
+    let mut previous = ' ';
+
+    for character in text.chars().flat_map(char::to_lowercase) {
+        match query_chars.peek() {
+            Some(&expected) if expected == character => {
+                query_chars.next();
+                consecutive += 1;
+                score += consecutive;
+
+                if !previous.is_alphanumeric() {
+                    score += 2;
+                }
+            }
+            Some(_) => consecutive = 0,
+            None => break,
+        }
+
+        previous = character;
+    }
+
+    if query_chars.peek().is_none() {
+        Some(score)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fuzzy_score;
+
+    #[test]
+    fn matches_characters_in_order() {
+        assert!(fuzzy_score("rst", "Rust presentations").is_some());
+        assert_eq!(fuzzy_score("tsr", "Rust"), None);
+    }
+
+    #[test]
+    fn every_query_character_must_match() {
+        assert_eq!(fuzzy_score("rusty", "Rust"), None);
+    }
+
+    #[test]
+    fn an_empty_query_matches_everything() {
+        assert_eq!(fuzzy_score("", "Anything"), Some(0));
+        assert_eq!(fuzzy_score("  ", ""), Some(0));
+    }
+
+    #[test]
+    fn ignores_case_and_query_whitespace() {
+        assert_eq!(
+            fuzzy_score("Slide Sync", "slidesync"),
+            fuzzy_score("slidesync", "slidesync"),
+        );
+    }
+
+    #[test]
+    fn prefers_consecutive_matches() {
+        let consecutive = fuzzy_score("abc", "xabcx").unwrap();
+        let scattered = fuzzy_score("abc", "xaxbxcx").unwrap();
+
+        assert!(consecutive > scattered);
+    }
+
+    #[test]
+    fn prefers_matches_at_the_start_of_words() {
+        let word_start = fuzzy_score("sync", "the sync").unwrap();
+        let inside_word = fuzzy_score("sync", "resync").unwrap();
+
+        assert!(word_start > inside_word);
+    }
+}
@@ -1,13 +1,24 @@
-use std::collections::HashSet;
-use std::{self, cmp};
+use std::cmp;
 
 use wasm_bindgen::JsCast;
 use web_sys::{Element, Node};
 
+use super::transitions::{direction_class, SlideTransition, StepEffect};
+use crate::manifest::SlideSummary;
+use crate::step_spec::{
+    StepContext, StepRange, StepSpec, StepSpecError, LABEL_ATTRIBUTE,
+    STEPS_ATTRIBUTE,
+};
+use crate::sync_message::PollResults;
+
 pub struct Slide {
     contents: Node,
     num_steps: usize,
     animated_elements: Vec<AnimatedElement>,
+    summary: SlideSummary,
+    transition: Option<SlideTransition>,
+    current_step: Option<usize>,
+    errors: Vec<StepSpecError>,
 }
 
 impl Slide {
@@ -31,29 +42,90 @@ impl Slide {
     pub fn new(contents: Node) -> Self {
         let mut animated_elements = Vec::new();
         let mut num_steps = 0;
+        let mut context = StepContext::new();
+        let mut errors = Vec::new();
+
+        Self::load_contents(
+            &contents,
+            &mut num_steps,
+            &mut animated_elements,
+            &mut context,
+            &mut errors,
+        );
 
-        Self::load_contents(&contents, &mut num_steps, &mut animated_elements);
+        let summary = Self::summarize(&contents);
+        let transition = Self::find_transition(&contents);
 
         Slide {
             contents,
             num_steps,
             animated_elements,
+            summary,
+            transition,
+            current_step: None,
+            errors,
         }
     }
 
+    fn find_transition(contents: &Node) -> Option<SlideTransition> {
+        let element = contents.dyn_ref::<Element>()?;
+        let selector = format!("[{}]", SlideTransition::ATTRIBUTE);
+        let transition_element =
+            if element.has_attribute(SlideTransition::ATTRIBUTE) {
+                element.clone()
+            } else {
+                element.query_selector(&selector).ok()??
+            };
+
+        transition_element
+            .get_attribute(SlideTransition::ATTRIBUTE)?
+            .parse()
+            .ok()
+    }
+
+    fn summarize(contents: &Node) -> SlideSummary {
+        let title = contents
+            .dyn_ref::<Element>()
+            .and_then(|element| {
+                element.query_selector("h1, h2, h3, h4, h5, h6").ok()
+            })
+            .flatten()
+            .and_then(|heading| heading.text_content())
+            .map(|title| Self::collapse_whitespace(&title))
+            .unwrap_or_default();
+        let text = contents
+            .text_content()
+            .map(|text| Self::collapse_whitespace(&text))
+            .unwrap_or_default();
+
+        SlideSummary { title, text }
+    }
+
+    fn collapse_whitespace(text: &str) -> String {
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
     fn load_contents(
         node: &Node,
         max_steps: &mut usize,
         animated_elements: &mut Vec<AnimatedElement>,
+        context: &mut StepContext,
+        errors: &mut Vec<StepSpecError>,
     ) {
         let maybe_element: Result<Element, _> = node.clone().dyn_into();
 
         if let Ok(element) = maybe_element {
-            if let Some(animated_element) = AnimatedElement::try_from(element) {
-                *max_steps =
-                    cmp::max(*max_steps, animated_element.last_known_step());
-
-                animated_elements.push(animated_element);
+            match AnimatedElement::try_from(element, context) {
+                Some(Ok(animated_element)) => {
+                    *max_steps = cmp::max(
+                        *max_steps,
+                        animated_element.last_known_step(),
+                    );
+
+                    animated_elements.push(animated_element);
+                }
+                Some(Err(error)) => errors.push(error),
+                None => {}
             }
         }
 
@@ -61,17 +133,89 @@ impl Slide {
 
         for index in 0..child_nodes.length() {
             if let Some(child) = child_nodes.get(index) {
-                Self::load_contents(&child, max_steps, animated_elements);
+                Self::load_contents(
+                    &child,
+                    max_steps,
+                    animated_elements,
+                    context,
+                    errors,
+                );
             }
         }
     }
 
     pub fn animate_for_step(&mut self, step: usize) {
+        let previous_step = self.current_step.replace(step);
+        let forward =
+            !matches!(previous_step, Some(previous) if step < previous);
+
         for animated_element in self.animated_elements.iter_mut() {
-            animated_element.animate_for_step(step);
+            animated_element.animate_for_step(step, forward);
         }
     }
 
+    pub fn reset_animation(&mut self) {
+        self.current_step = None;
+
+        for animated_element in self.animated_elements.iter_mut() {
+            animated_element.shown = None;
+        }
+    }
+
+    /// Shows the number of votes next to each option of a poll, if the poll
+    /// is on this slide.
+    pub fn show_poll_results(&self, results: &PollResults) {
+        for (option, element) in self.poll_options(results.poll) {
+            let votes = results.votes.get(option).copied().unwrap_or(0);
+
+            if let Ok(Some(count)) = element.query_selector(".poll-votes") {
+                count.set_text_content(Some(&format!("({})", votes)));
+            }
+        }
+    }
+
+    /// Highlights the option of a poll that was voted for.
+    pub fn mark_vote(&self, poll: u32, option: u32) {
+        for (index, element) in self.poll_options(poll) {
+            let _ = if index == option as usize {
+                element.set_attribute("data-poll-voted", "")
+            } else {
+                element.remove_attribute("data-poll-voted")
+            };
+        }
+    }
+
+    fn poll_options(&self, poll: u32) -> Vec<(usize, Element)> {
+        let selector =
+            format!(".poll[data-poll=\"{}\"] [data-poll-option]", poll);
+        let options = match self
+            .contents
+            .dyn_ref::<Element>()
+            .and_then(|element| element.query_selector_all(&selector).ok())
+        {
+            Some(options) => options,
+            None => return Vec::new(),
+        };
+
+        (0..options.length())
+            .filter_map(|index| options.get(index)?.dyn_into::<Element>().ok())
+            .filter_map(|element| {
+                let option =
+                    element.get_attribute("data-poll-option")?.parse().ok()?;
+
+                Some((option, element))
+            })
+            .collect()
+    }
+
+    pub fn transition(&self) -> Option<SlideTransition> {
+        self.transition
+    }
+
+    pub fn errors(&self) -> &[StepSpecError] {
+        &self.errors
+    }
+
     pub fn as_node(&self) -> Result<Node, String> {
         self.contents
             .clone_node_with_deep(true)
@@ -81,143 +225,105 @@ impl Slide {
     pub fn num_steps(&self) -> usize {
         self.num_steps
     }
+
+    pub fn summary(&self) -> &SlideSummary {
+        &self.summary
+    }
 }
 
 #[derive(Clone, Debug)]
 struct AnimatedElement {
     element: Element,
     class: Option<String>,
-    steps: HashSet<usize>,
-    always_show_after: Option<usize>,
+    ranges: Vec<StepRange>,
     last_known_step: usize,
+    effect: Option<StepEffect>,
+    shown: Option<bool>,
 }
 
 impl AnimatedElement {
-    pub fn try_from(element: Element) -> Option<Self> {
-        if let Some(step_spec) = element.get_attribute("data-slide-steps") {
-            let class = element.get_attribute("class").map(|mut class| {
-                if !class.ends_with(';') {
-                    class.push(';');
-                }
-                class
-            });
-
-            let mut steps = HashSet::new();
-            let mut always_show_after = None;
-            let mut last_known_step = 0;
-
-            for range in Self::step_ranges(step_spec.trim()) {
-                match range {
-                    (Some(start), Some(end)) => {
-                        for step in start..=end {
-                            steps.insert(step);
-                        }
-
-                        last_known_step = cmp::max(last_known_step, end);
-                    }
-                    (Some(start), None) => {
-                        steps.insert(start);
-
-                        last_known_step = cmp::max(last_known_step, start);
-
-                        let previous_start =
-                            always_show_after.take().unwrap_or(std::usize::MAX);
-                        always_show_after =
-                            Some(cmp::min(start, previous_start));
-                    }
-                    (None, Some(end)) => {
-                        for step in 1..end {
-                            steps.insert(step);
-                        }
-
-                        last_known_step = cmp::max(last_known_step, end);
-                    }
-                    _ => {}
-                }
-            }
+    pub fn try_from(
+        element: Element,
+        context: &mut StepContext,
+    ) -> Option<Result<Self, StepSpecError>> {
+        let step_spec = element.get_attribute(STEPS_ATTRIBUTE)?;
+        let ranges = match StepSpec::parse(step_spec.trim())
+            .and_then(|spec| spec.resolve(context))
+        {
+            Ok(ranges) => ranges,
+            Err(error) => return Some(Err(error)),
+        };
 
-            Some(AnimatedElement {
-                element,
-                class,
-                steps,
-                always_show_after,
-                last_known_step,
-            })
-        } else {
-            None
-        }
-    }
+        context.advance(&ranges);
 
-    fn step_ranges<'a>(
-        step_spec: &'a str,
-    ) -> impl Iterator<Item = (Option<usize>, Option<usize>)> + 'a {
-        step_spec.split(',').map(|range| {
-            if range == "-" {
-                (Some(1), None)
-            } else if range.starts_with('-') {
-                let last = range
-                    .split('-')
-                    .last()
-                    .and_then(|step: &str| step.parse().ok());
-
-                if last.is_some() {
-                    (Some(1), last)
-                } else {
-                    (None, None)
-                }
-            } else if range.ends_with('-') {
-                let first = range
-                    .split('-')
-                    .next()
-                    .and_then(|step: &str| step.parse().ok());
-
-                if first.is_some() {
-                    (first, None)
-                } else {
-                    (None, None)
-                }
-            } else if range.contains('-') {
-                let mut steps = range.split('-');
-                let first =
-                    steps.next().and_then(|step: &str| step.parse().ok());
-                let last =
-                    steps.last().and_then(|step: &str| step.parse().ok());
-
-                if first.is_some() && last.is_some() {
-                    (first, last)
-                } else {
-                    (None, None)
-                }
-            } else {
-                let step = range.parse().ok();
+        if let Some(label) = element.get_attribute(LABEL_ATTRIBUTE) {
+            context.define_label(label.trim(), &ranges);
+        }
 
-                (step, step)
+        let class = element.get_attribute("class").map(|mut class| {
+            if !class.ends_with(';') {
+                class.push(';');
             }
-        })
+            class
+        });
+        let last_known_step = ranges
+            .iter()
+            .map(StepRange::last_known_step)
+            .max()
+            .unwrap_or(0);
+        let effect = element
+            .get_attribute(StepEffect::ATTRIBUTE)
+            .and_then(|effect| effect.parse().ok());
+
+        Some(Ok(AnimatedElement {
+            element,
+            class,
+            ranges,
+            last_known_step,
+            effect,
+            shown: None,
+        }))
     }
 
-    pub fn animate_for_step(&mut self, step: usize) {
-        let extra_class = if self.is_shown_in_step(step) {
-            "active-in-slide-step"
+    pub fn animate_for_step(&mut self, step: usize, forward: bool) {
+        let shown = self.is_shown_in_step(step);
+        let was_shown = self.shown.replace(shown);
+
+        let mut extra_class = if shown {
+            "active-in-slide-step".to_owned()
         } else {
-            "inactive-in-slide-step"
+            "inactive-in-slide-step".to_owned()
         };
 
+        if let Some(effect) = self.effect {
+            extra_class.push_str(&format!(
+                " step-effect-{} step-{}",
+                effect.name(),
+                direction_class(forward),
+            ));
+
+            match was_shown {
+                Some(false) if shown => extra_class.push_str(" step-entering"),
+                Some(true) if !shown => extra_class.push_str(" step-leaving"),
+                _ => {}
+            }
+
+            if !shown && step > self.last_known_step {
+                extra_class.push_str(" step-past");
+            }
+        }
+
         let class = self
             .class
             .as_ref()
             .map(|class| format!("{} {}", class, extra_class))
-            .unwrap_or_else(|| extra_class.to_owned());
+            .unwrap_or(extra_class);
 
         let _ = self.element.set_attribute("class", &class);
     }
 
     pub fn is_shown_in_step(&mut self, step: usize) -> bool {
-        if let Some(always_show_after) = self.always_show_after {
-            step >= always_show_after || self.steps.contains(&step)
-        } else {
-            self.steps.contains(&step)
-        }
+        self.ranges.iter().any(|range| range.contains(step))
     }
 
     pub fn last_known_step(&self) -> usize {
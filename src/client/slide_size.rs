@@ -1,4 +1,7 @@
-use std::fmt::{self, Display, Formatter};
+use {
+    crate::config::{SlideFit, SlideFormat},
+    std::fmt::{self, Display, Formatter},
+};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Direction {
@@ -44,12 +47,30 @@ impl SlideMeasure {
             unfilled_direction,
         }
     }
+
+    pub fn unfilled_direction(&self) -> Direction {
+        self.unfilled_direction
+    }
+
+    pub fn filled_size(&self) -> (f64, f64) {
+        match self.unfilled_direction() {
+            Direction::Vertical => {
+                (self.width / self.scale, self.container_height / self.scale)
+            }
+            Direction::Horizontal => {
+                (self.container_width / self.scale, self.height / self.scale)
+            }
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct SlideSize {
     original_width: f64,
     original_height: f64,
+    width: f64,
+    height: f64,
+    fit: SlideFit,
     scale: f64,
     translate_x: f64,
     translate_y: f64,
@@ -58,6 +79,9 @@ pub struct SlideSize {
     margin_left: f64,
     margin_right: f64,
     unfilled_direction: Direction,
+    zoom: f64,
+    pan_x: f64,
+    pan_y: f64,
 }
 
 impl Default for SlideSize {
@@ -67,10 +91,15 @@ impl Default for SlideSize {
 }
 
 impl SlideSize {
+    const MAX_ZOOM: f64 = 4.0;
+
     pub fn new(width: f64, height: f64) -> Self {
         SlideSize {
             original_width: width,
             original_height: height,
+            width,
+            height,
+            fit: SlideFit::Letterbox,
             scale: 1.0,
             translate_x: 0.0,
             translate_y: 0.0,
@@ -79,11 +108,59 @@ impl SlideSize {
             margin_left: 0.0,
             margin_right: 0.0,
             unfilled_direction: Direction::Horizontal,
+            zoom: 1.0,
+            pan_x: 0.0,
+            pan_y: 0.0,
         }
     }
 
+    pub fn from_format(format: SlideFormat, fit: SlideFit) -> Self {
+        let mut slide_size = SlideSize::new(format.width, format.height);
+
+        slide_size.fit = fit;
+        slide_size
+    }
+
+    pub fn set_format(&mut self, format: SlideFormat, fit: SlideFit) {
+        self.original_width = format.width;
+        self.original_height = format.height;
+        self.width = format.width;
+        self.height = format.height;
+        self.fit = fit;
+    }
+
+    /// The size of the slide before it is scaled to fit the window.
+    pub fn logical_size(&self) -> (f64, f64) {
+        (self.width, self.height)
+    }
+
+    pub fn is_zoomed(&self) -> bool {
+        self.zoom > 1.0
+    }
+
+    pub fn zoom_by(&mut self, factor: f64) {
+        self.zoom = (self.zoom * factor).clamp(1.0, Self::MAX_ZOOM);
+
+        if !self.is_zoomed() {
+            self.reset_zoom();
+        }
+    }
+
+    pub fn pan_by(&mut self, delta_x: f64, delta_y: f64) {
+        if self.is_zoomed() {
+            self.pan_x += delta_x;
+            self.pan_y += delta_y;
+        }
+    }
+
+    pub fn reset_zoom(&mut self) {
+        self.zoom = 1.0;
+        self.pan_x = 0.0;
+        self.pan_y = 0.0;
+    }
+
     pub fn measure_to_fit_in(&self, width: f64, height: f64) -> SlideMeasure {
-        SlideMeasure::measure_to_fit(&self, width, height)
+        SlideMeasure::measure_to_fit(self, width, height)
     }
 
     pub fn resize_to_fit_in(&mut self, width: f64, height: f64) {
@@ -95,14 +172,22 @@ impl SlideSize {
     pub fn resize(&mut self, measure: SlideMeasure) {
         self.scale = measure.scale;
 
-        let delta_x = measure.width - self.original_width;
-        let delta_y = measure.height - self.original_height;
+        let (width, height) = match self.fit {
+            SlideFit::Letterbox => (self.original_width, self.original_height),
+            SlideFit::Fill => measure.filled_size(),
+        };
+
+        self.width = width;
+        self.height = height;
+
+        let scaled_width = width * measure.scale;
+        let scaled_height = height * measure.scale;
 
-        self.translate_x = delta_x / 2.0;
-        self.translate_y = delta_y / 2.0;
+        self.translate_x = (scaled_width - width) / 2.0;
+        self.translate_y = (scaled_height - height) / 2.0;
 
-        let horizontal_margin = (measure.container_width - measure.width) / 2.0;
-        let vertical_margin = (measure.container_height - measure.height) / 2.0;
+        let horizontal_margin = (measure.container_width - scaled_width) / 2.0;
+        let vertical_margin = (measure.container_height - scaled_height) / 2.0;
 
         self.margin_left = horizontal_margin;
         self.margin_right = horizontal_margin;
@@ -117,12 +202,14 @@ impl Display for SlideSize {
         write!(formatter, " position: absolute;")?;
         write!(formatter, " top: 0;")?;
         write!(formatter, " left: 0;")?;
-        write!(formatter, " width: {}px;", self.original_width)?;
-        write!(formatter, " height: {}px;", self.original_height)?;
+        write!(formatter, " width: {}px;", self.width)?;
+        write!(formatter, " height: {}px;", self.height)?;
         write!(
             formatter,
             " transform: translate({}px, {}px) scale({});",
-            self.translate_x, self.translate_y, self.scale,
+            self.translate_x + self.pan_x,
+            self.translate_y + self.pan_y,
+            self.scale * self.zoom,
         )?;
 
         if self.unfilled_direction == Direction::Vertical {
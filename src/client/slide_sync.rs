@@ -1,22 +1,49 @@
 use {
-    std::{convert::TryInto, mem},
+    super::questions::QuestionPanel,
+    crate::sync_message::{
+        Annotation, ClientMessage, PollResults, Question, Reaction, Role,
+        RosterSummary, ServerMessage, SyncPosition,
+    },
+    std::{collections::BTreeMap, convert::TryInto, mem, time::Duration},
+    web_sys::Url,
     yew::{
-        format::Binary,
+        format::{Binary, Json, Text},
         prelude::*,
-        services::websocket::{
-            WebSocketService, WebSocketStatus, WebSocketTask,
+        services::{
+            timeout::{TimeoutService, TimeoutTask},
+            websocket::{WebSocketService, WebSocketStatus, WebSocketTask},
         },
     },
 };
 
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_millis(500);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// The query parameter of the page with the server's presenter token.
+const PRESENTER_TOKEN_PARAMETER: &str = "presenter-token";
+
+/// The local storage key of the id the client keeps across reconnections.
+const CLIENT_ID_KEY: &str = "presentrs-client-id";
+
 pub struct SlideSync {
     component_link: ComponentLink<Self>,
     url: String,
+    identity: Identity,
     state: State,
+    open: bool,
+    failed_attempts: u32,
+    roster: Option<RosterSummary>,
+    questions: Vec<Question>,
     presenting: bool,
+    /// Whether the server refused to let the client present.
+    presenting_refused: bool,
     current_slide: usize,
     current_step: usize,
     on_update_position: Callback<(u16, u16)>,
+    on_annotation: Callback<Annotation>,
+    on_poll_results: Callback<PollResults>,
+    on_reactions: Callback<BTreeMap<Reaction, u32>>,
+    on_presenting_refused: Callback<()>,
 }
 
 #[derive(Clone, Debug, Properties)]
@@ -26,20 +53,63 @@ pub struct Properties {
     pub current_slide: usize,
     pub current_step: usize,
     pub on_update_position: Callback<(u16, u16)>,
+    /// Receives the annotations made by other presenters.
+    pub on_annotation: Callback<Annotation>,
+    /// Receives a callback that sends annotations while presenting.
+    pub on_annotation_sender: Callback<Callback<Annotation>>,
+    /// Receives the live results of polls, if the server shares them.
+    pub on_poll_results: Callback<PollResults>,
+    /// Receives the reactions of the audience while presenting.
+    pub on_reactions: Callback<BTreeMap<Reaction, u32>>,
+    /// Receives a callback that sends messages to the server while
+    /// connected.
+    pub on_message_sender: Callback<Callback<ClientMessage>>,
+    /// Called when the server refuses to let the client present, which it
+    /// keeps following the presentation instead.
+    pub on_presenting_refused: Callback<()>,
 }
 
 pub enum Message {
     Connected,
     Disconnected,
+    Reconnect,
     ToggleSync,
     Update { slide: u16, step: u16 },
+    Roster(RosterSummary),
+    PresentingRefused,
+    Annotate(Annotation),
+    AnnotationReceived(Annotation),
+    PollResults(PollResults),
+    Questions(Vec<Question>),
+    Reactions(BTreeMap<Reaction, u32>),
+    Send(ClientMessage),
     Ignore,
 }
 
+/// A message received from the server, either as text or as binary.
+enum Frame {
+    Text(Text),
+    Binary(Binary),
+}
+
+/// How the client identifies itself to the server with its status.
+struct Identity {
+    /// A random id kept in local storage, so that the server recognizes the
+    /// client's votes when it reconnects.
+    client_id: String,
+    /// The presenter token the page was opened with, which the server asks
+    /// for before letting a client present.
+    presenter_token: Option<String>,
+}
+
 enum State {
     Offline,
     Syncing(WebSocketTask),
     Presenting(WebSocketTask),
+    Reconnecting {
+        presenting: bool,
+        _timeout: TimeoutTask,
+    },
 }
 
 impl SlideSync {
@@ -47,11 +117,16 @@ impl SlideSync {
         let previous_state = mem::replace(&mut self.state, State::Offline);
 
         if self.presenting {
+            self.presenting_refused = false;
             self.state = match previous_state {
-                State::Offline => State::Presenting(self.connect()),
+                State::Offline => self.connect(true),
                 State::Syncing(connection) | State::Presenting(connection) => {
                     State::Presenting(connection)
                 }
+                State::Reconnecting { _timeout, .. } => State::Reconnecting {
+                    presenting: true,
+                    _timeout,
+                },
             };
         } else {
             self.state = match previous_state {
@@ -59,6 +134,10 @@ impl SlideSync {
                 State::Syncing(connection) | State::Presenting(connection) => {
                     State::Syncing(connection)
                 }
+                State::Reconnecting { _timeout, .. } => State::Reconnecting {
+                    presenting: false,
+                    _timeout,
+                },
             };
         }
 
@@ -69,48 +148,121 @@ impl SlideSync {
         let previous_state = mem::replace(&mut self.state, State::Offline);
 
         self.state = match previous_state {
-            State::Offline => State::Syncing(self.connect()),
-            State::Syncing(_) => State::Offline,
+            State::Offline => {
+                self.failed_attempts = 0;
+                self.connect(false)
+            }
+            State::Syncing(_) | State::Reconnecting { .. } => State::Offline,
             State::Presenting(connection) => State::Syncing(connection),
         };
 
         self.presenting = false;
+        self.presenting_refused = false;
 
         true
     }
 
-    fn connect(&mut self) -> WebSocketTask {
-        for _attempt in 1..10 {
-            let connection = WebSocketService::connect_binary(
-                &self.url,
-                self.component_link.callback(Self::ws_message_handler),
-                self.component_link.callback(Self::ws_event_handler),
-            );
+    fn connect(&mut self, presenting: bool) -> State {
+        self.open = false;
+        self.roster = None;
+        self.questions.clear();
 
-            if let Ok(connection) = connection {
-                return connection;
-            }
+        let connection = WebSocketService::connect(
+            &self.url,
+            self.component_link.callback(Self::ws_message_handler),
+            self.component_link.callback(Self::ws_event_handler),
+        );
+
+        match connection {
+            Ok(connection) if presenting => State::Presenting(connection),
+            Ok(connection) => State::Syncing(connection),
+            Err(_) => self.schedule_reconnect(presenting),
+        }
+    }
+
+    fn connected(&mut self) -> ShouldRender {
+        self.open = true;
+        self.failed_attempts = 0;
+        self.send_status();
+
+        if let State::Presenting(_) = self.state {
+            self.send_position()
+        } else {
+            self.state.request_position();
+            false
         }
+    }
+
+    fn disconnected(&mut self) -> ShouldRender {
+        let presenting = match self.state {
+            State::Syncing(_) => false,
+            State::Presenting(_) => true,
+            State::Offline | State::Reconnecting { .. } => return false,
+        };
+
+        self.state = self.schedule_reconnect(presenting);
 
-        panic!("Failed to connect for synchronization");
+        true
     }
 
     fn reconnect(&mut self) -> ShouldRender {
-        match mem::replace(&mut self.state, State::Offline) {
-            State::Offline => {}
-            State::Syncing(_) => self.state = State::Syncing(self.connect()),
-            State::Presenting(_) => {
-                self.state = State::Presenting(self.connect())
-            }
+        if let State::Reconnecting { presenting, .. } = self.state {
+            self.state = self.connect(presenting);
         }
 
         false
     }
 
-    fn ws_message_handler(message_bytes: Binary) -> Message {
-        let message_bytes = match message_bytes {
-            Ok(bytes) => bytes,
-            Err(_) => return Message::Ignore,
+    /// Waits before reconnecting, doubling the delay after each failed
+    /// attempt and randomizing it so that clients don't reconnect together.
+    fn schedule_reconnect(&mut self, presenting: bool) -> State {
+        let backoff = INITIAL_RECONNECT_DELAY
+            .checked_mul(1 << self.failed_attempts.min(16))
+            .unwrap_or(MAX_RECONNECT_DELAY)
+            .min(MAX_RECONNECT_DELAY);
+        let delay = backoff.mul_f64(0.5 + js_sys::Math::random() / 2.0);
+
+        self.failed_attempts = self.failed_attempts.saturating_add(1);
+
+        State::Reconnecting {
+            presenting,
+            _timeout: TimeoutService::spawn(
+                delay,
+                self.component_link.callback(|_| Message::Reconnect),
+            ),
+        }
+    }
+
+    fn ws_message_handler(frame: Frame) -> Message {
+        let message_bytes = match frame {
+            Frame::Binary(Ok(bytes)) => bytes,
+            Frame::Text(Ok(text)) => {
+                return match serde_json::from_str(&text) {
+                    Ok(ServerMessage::Role { refused: true, .. }) => {
+                        Message::PresentingRefused
+                    }
+                    Ok(ServerMessage::Role { .. }) => Message::Ignore,
+                    Ok(ServerMessage::Roster(roster)) => {
+                        Message::Roster(roster)
+                    }
+                    Ok(ServerMessage::Annotate { annotation }) => {
+                        Message::AnnotationReceived(annotation)
+                    }
+                    Ok(ServerMessage::PollResults(results)) => {
+                        Message::PollResults(results)
+                    }
+                    Ok(ServerMessage::Questions { questions }) => {
+                        Message::Questions(questions)
+                    }
+                    Ok(ServerMessage::Reactions { reactions }) => {
+                        Message::Reactions(reactions)
+                    }
+                    Err(_) => Message::Ignore,
+                };
+            }
+            Frame::Binary(Err(_)) | Frame::Text(Err(_)) => {
+                return Message::Ignore
+            }
         };
 
         if message_bytes.len() == 4 {
@@ -134,20 +286,124 @@ impl SlideSync {
         }
     }
 
+    fn position(&self) -> SyncPosition {
+        SyncPosition {
+            slide: self.current_slide.try_into().unwrap_or(u16::MAX),
+            step: self.current_step.try_into().unwrap_or(u16::MAX),
+        }
+    }
+
     fn send_position(&mut self) -> ShouldRender {
-        let slide = self.current_slide.try_into().unwrap_or(u16::MAX);
-        let step = self.current_step.try_into().unwrap_or(u16::MAX);
+        if self.open {
+            let position = self.position();
 
-        self.state.send_position(slide, step);
+            self.state.send_position(position, &self.identity);
+        }
 
         false
     }
 
+    fn send_status(&mut self) {
+        if self.open {
+            let position = self.position();
+
+            self.state.send_status(position, &self.identity);
+        }
+    }
+
+    /// Keeps following the presentation when the server refuses to let the
+    /// client present.
+    fn presenting_refused(&mut self) -> ShouldRender {
+        if let State::Presenting(connection) =
+            mem::replace(&mut self.state, State::Offline)
+        {
+            self.state = State::Syncing(connection);
+            self.state.request_position();
+        }
+
+        self.presenting = false;
+        self.presenting_refused = true;
+        self.on_presenting_refused.emit(());
+
+        true
+    }
+
     fn update(&mut self, slide: u16, step: u16) -> ShouldRender {
         self.on_update_position.emit((slide, step));
         false
     }
 
+    // The `QuestionPanel` properties set in `html!` trip clippy.
+    #[allow(clippy::unnecessary_operation)]
+    fn view_questions(&self) -> Html {
+        let on_ask = self
+            .component_link
+            .callback(|text| Message::Send(ClientMessage::Ask { text }));
+        let on_upvote = self.component_link.callback(|question| {
+            Message::Send(ClientMessage::Upvote { question })
+        });
+        let on_dismiss = self.component_link.callback(|question| {
+            Message::Send(ClientMessage::Dismiss { question })
+        });
+
+        html! {
+            <QuestionPanel
+                presenting = self.presenting
+                questions = self.questions.clone()
+                on_ask = on_ask
+                on_upvote = on_upvote
+                on_dismiss = on_dismiss
+                />
+        }
+    }
+
+    fn view_reactions(&self) -> Html {
+        html! {
+            <span style="margin-left: 5px; vertical-align: top">
+                { for Reaction::ALL.iter().map(|&reaction| {
+                    let react = self.component_link.callback(move |_| {
+                        Message::Send(ClientMessage::React { reaction })
+                    });
+
+                    html! {
+                        <button onclick = react>{ reaction.emoji() }</button>
+                    }
+                }) }
+            </span>
+        }
+    }
+
+    fn view_refusal(&self) -> Html {
+        if !self.presenting_refused {
+            return html! {};
+        }
+
+        html! {
+            <span style="margin-left: 5px; vertical-align: top; color: red">
+                { "Presenting needs the presenter token" }
+            </span>
+        }
+    }
+
+    fn view_roster(&self) -> Html {
+        let roster = match &self.roster {
+            Some(roster) => roster,
+            None => return html! {},
+        };
+
+        let drifted = if roster.drifted > 0 {
+            format!(", {} elsewhere", roster.drifted)
+        } else {
+            String::new()
+        };
+
+        html! {
+            <span style="margin-left: 5px; vertical-align: top">
+                { format!("{} watching{}", roster.audience, drifted) }
+            </span>
+        }
+    }
+
     fn apply_change<T: PartialEq>(target: &mut T, source: T) -> bool {
         if *target != source {
             *target = source;
@@ -158,20 +414,127 @@ impl SlideSync {
     }
 }
 
+impl Identity {
+    fn load() -> Self {
+        Identity {
+            client_id: Self::client_id(),
+            presenter_token: Self::presenter_token(),
+        }
+    }
+
+    fn client_id() -> String {
+        let storage = web_sys::window()
+            .and_then(|window| window.local_storage().ok())
+            .flatten();
+        let stored_id = storage
+            .as_ref()
+            .and_then(|storage| storage.get_item(CLIENT_ID_KEY).ok())
+            .flatten();
+
+        if let Some(client_id) = stored_id {
+            return client_id;
+        }
+
+        let client_id: String = (0..4)
+            .map(|_| {
+                let random = js_sys::Math::random() * f64::from(u32::MAX);
+
+                format!("{:08x}", random as u32)
+            })
+            .collect();
+
+        if let Some(storage) = storage {
+            // Without storage, the id only lasts until the page is closed.
+            let _ = storage.set_item(CLIENT_ID_KEY, &client_id);
+        }
+
+        client_id
+    }
+
+    fn presenter_token() -> Option<String> {
+        let href = web_sys::window()?.location().href().ok()?;
+
+        Url::new(&href)
+            .ok()?
+            .search_params()
+            .get(PRESENTER_TOKEN_PARAMETER)
+    }
+}
+
 impl State {
-    pub fn send_position(&mut self, slide: u16, step: u16) {
+    /// Sends a presenter's position to everyone, or tells the server where
+    /// an audience member is.
+    pub fn send_position(
+        &mut self,
+        position: SyncPosition,
+        identity: &Identity,
+    ) {
         match self {
-            State::Offline | State::Syncing(_) => {}
+            State::Offline | State::Reconnecting { .. } => {}
+            State::Syncing(_) => self.send_status(position, identity),
             State::Presenting(connection) => {
                 let mut message = Vec::with_capacity(4);
 
-                message.extend(slide.to_be_bytes());
-                message.extend(step.to_be_bytes());
+                message.extend(position.slide.to_be_bytes());
+                message.extend(position.step.to_be_bytes());
 
                 connection.send_binary(Ok(message));
             }
         }
     }
+
+    pub fn send_status(&mut self, position: SyncPosition, identity: &Identity) {
+        let (connection, role, token) = match self {
+            State::Offline | State::Reconnecting { .. } => return,
+            State::Syncing(connection) => (connection, Role::Audience, None),
+            State::Presenting(connection) => (
+                connection,
+                Role::Presenter,
+                identity.presenter_token.clone(),
+            ),
+        };
+
+        connection.send(Json(&ClientMessage::Status {
+            role,
+            token,
+            client: Some(identity.client_id.clone()),
+            position,
+        }));
+    }
+
+    /// Sends a message to the server while connected.
+    pub fn send(&mut self, message: &ClientMessage) {
+        if let State::Syncing(connection) | State::Presenting(connection) = self
+        {
+            connection.send(Json(message));
+        }
+    }
+
+    pub fn send_annotation(&mut self, annotation: Annotation) {
+        if let State::Presenting(connection) = self {
+            connection.send(Json(&ClientMessage::Annotate { annotation }));
+        }
+    }
+
+    /// Asks the server for the presenter's current position, which is sent
+    /// in reply to an empty message.
+    pub fn request_position(&mut self) {
+        if let State::Syncing(connection) = self {
+            connection.send_binary(Ok(Vec::new()));
+        }
+    }
+}
+
+impl From<Text> for Frame {
+    fn from(text: Text) -> Self {
+        Frame::Text(text)
+    }
+}
+
+impl From<Binary> for Frame {
+    fn from(binary: Binary) -> Self {
+        Frame::Binary(binary)
+    }
 }
 
 impl Component for SlideSync {
@@ -182,14 +545,31 @@ impl Component for SlideSync {
         properties: Self::Properties,
         component_link: ComponentLink<Self>,
     ) -> Self {
+        properties
+            .on_annotation_sender
+            .emit(component_link.callback(Message::Annotate));
+        properties
+            .on_message_sender
+            .emit(component_link.callback(Message::Send));
+
         SlideSync {
             component_link,
             url: properties.url,
+            identity: Identity::load(),
             state: State::Offline,
+            open: false,
+            failed_attempts: 0,
+            roster: None,
+            questions: Vec::new(),
             presenting: properties.presenting,
+            presenting_refused: false,
             current_slide: properties.current_slide,
             current_step: properties.current_step,
             on_update_position: properties.on_update_position,
+            on_annotation: properties.on_annotation,
+            on_poll_results: properties.on_poll_results,
+            on_reactions: properties.on_reactions,
+            on_presenting_refused: properties.on_presenting_refused,
         }
     }
 
@@ -207,6 +587,7 @@ impl Component for SlideSync {
 
         if presenting_changed {
             self.present();
+            self.send_status();
         }
 
         if position_changed {
@@ -220,10 +601,46 @@ impl Component for SlideSync {
 
     fn update(&mut self, message: Self::Message) -> ShouldRender {
         match message {
-            Message::Connected => self.send_position(),
-            Message::Disconnected => self.reconnect(),
+            Message::Connected => self.connected(),
+            Message::Disconnected => self.disconnected(),
+            Message::Reconnect => self.reconnect(),
             Message::ToggleSync => self.toggle_sync(),
             Message::Update { slide, step } => self.update(slide, step),
+            Message::Roster(roster) => {
+                self.roster = Some(roster);
+                true
+            }
+            Message::PresentingRefused => self.presenting_refused(),
+            Message::Annotate(annotation) => {
+                if self.open {
+                    self.state.send_annotation(annotation);
+                }
+
+                false
+            }
+            Message::AnnotationReceived(annotation) => {
+                self.on_annotation.emit(annotation);
+                false
+            }
+            Message::PollResults(results) => {
+                self.on_poll_results.emit(results);
+                false
+            }
+            Message::Questions(questions) => {
+                self.questions = questions;
+                true
+            }
+            Message::Reactions(reactions) => {
+                self.on_reactions.emit(reactions);
+                false
+            }
+            Message::Send(message) => {
+                if self.open {
+                    self.state.send(&message);
+                }
+
+                false
+            }
             Message::Ignore => false,
         }
     }
@@ -271,6 +688,9 @@ impl Component for SlideSync {
                                 style="fill: black"
                                 />
                         </svg>
+                        { self.view_reactions() }
+                        { self.view_refusal() }
+                        { self.view_questions() }
                     </div>
                 }
             }
@@ -288,6 +708,29 @@ impl Component for SlideSync {
                                 style="fill: black"
                                 />
                         </svg>
+                        { self.view_roster() }
+                        { self.view_questions() }
+                    </div>
+                }
+            }
+            State::Reconnecting { .. } => {
+                html! {
+                    <div style="float: left; margin: 10px">
+                        <svg
+                            viewBox="-70 -70 140 140"
+                            style="height: 20px"
+                            onclick = toggle_sync
+                            >
+                            <title>{ "Reconnecting" }</title>
+                            <circle
+                                cx = 0
+                                cy = 0
+                                r = 50
+                                style="fill: none; stroke: gray; \
+                                       stroke-width: 20; \
+                                       stroke-dasharray: 40 20"
+                                />
+                        </svg>
                     </div>
                 }
             }
@@ -1,14 +1,52 @@
 use {
-    super::{slide::Slide, slide_size::SlideSize},
+    super::{
+        annotations::Annotations,
+        base_url,
+        slide::Slide,
+        slide_size::SlideSize,
+        styles,
+        transitions::{
+            direction_class, SlideTransition, TRANSITIONS_CSS,
+            TRANSITION_DURATION,
+        },
+    },
+    crate::{
+        manifest::{Manifest, SlideSummary},
+        sync_message::{Annotation, PollResults, SlidePoint},
+    },
     lru::LruCache,
+    std::{collections::BTreeMap, mem, rc::Rc},
+    wasm_bindgen::JsCast,
+    web_sys::{Element, MouseEvent},
     yew::{
         format::{Nothing, Text},
         prelude::*,
-        services::fetch::{FetchService, FetchTask, Request, Response},
+        services::{
+            fetch::{FetchService, FetchTask, Request, Response},
+            render::{RenderService, RenderTask},
+            timeout::{TimeoutService, TimeoutTask},
+        },
         virtual_dom::VNode,
     },
 };
 
+const STEP_SPEC_ERRORS_STYLE: &str = "\
+    position: absolute;\
+    left: 0;\
+    right: 0;\
+    bottom: 0;\
+    padding: 0.5em 1em;\
+    background: rgba(128, 0, 0, 0.9);\
+    color: white;\
+    font-family: monospace;\
+    font-size: 14px;\
+";
+
+const POLLS_CSS: &str = "\
+    .poll-option { cursor: pointer; }\
+    .poll-option[data-poll-voted] { font-weight: bold; }\
+";
+
 pub struct Slides {
     locale_path: String,
     slide_cache: LruCache<usize, Status>,
@@ -16,10 +54,73 @@ pub struct Slides {
     current_step: usize,
     link: ComponentLink<Slides>,
     size: SlideSize,
-    on_slide_loaded: Option<Callback<(usize, usize)>>,
+    on_slide_loaded: Option<Callback<(usize, usize, SlideSummary)>>,
+    on_manifest_loaded: Option<Callback<Manifest>>,
+    manifest_task: Option<FetchTask>,
+    transition: Option<ActiveTransition>,
+    annotations: Rc<Annotations>,
+    on_annotate: Option<Callback<Annotation>>,
+    annotation_layer: NodeRef,
+    drawing: Option<u32>,
+    next_stroke_id: u32,
+    /// The pointer position and stroke points waiting for the next animation
+    /// frame, so that mouse moves are sent in batches.
+    pending_annotations: PendingAnnotations,
+    annotation_frame: Option<RenderTask>,
+    poll_results: Rc<BTreeMap<u32, PollResults>>,
+    on_vote: Option<Callback<(u32, u32)>>,
+}
+
+#[derive(Default)]
+struct PendingAnnotations {
+    pointer: Option<Option<SlidePoint>>,
+    stroke: Option<(u32, Vec<SlidePoint>)>,
+}
+
+struct ActiveTransition {
+    previous_slide: usize,
+    effect: SlideTransition,
+    forward: bool,
+    _timeout: TimeoutTask,
 }
 
 impl Slides {
+    fn locale_path(locale: Option<String>) -> String {
+        locale
+            .map(|mut locale| {
+                locale.push('/');
+                locale
+            })
+            .unwrap_or_default()
+    }
+
+    fn fetch_manifest(&mut self) {
+        let request = Request::get(base_url::resolve(&format!(
+            "slides/{}{}",
+            self.locale_path,
+            Manifest::FILE_NAME,
+        )))
+        .body(Nothing);
+
+        if let Ok(request) = request {
+            let fetch_task = FetchService::fetch(
+                request,
+                self.link.callback(|response: Response<Text>| {
+                    let (meta, body) = response.into_parts();
+
+                    let manifest = body
+                        .ok()
+                        .filter(|_| meta.status.is_success())
+                        .and_then(|body| serde_json::from_str(&body).ok());
+
+                    Message::ManifestLoaded(manifest)
+                }),
+            );
+
+            self.manifest_task = fetch_task.ok();
+        }
+    }
+
     fn fetch_slides(&mut self) {
         for offset in -1..3 {
             let index = self.current_slide as isize + offset;
@@ -36,10 +137,10 @@ impl Slides {
             Some(Status::Loading(_)) | Some(Status::Ready(_)) => return,
         }
 
-        let request = Request::get(format!(
-            "/slides/{}{}.html",
+        let request = Request::get(base_url::resolve(&format!(
+            "slides/{}{}.html",
             self.locale_path, slide_index
-        ))
+        )))
         .body(Nothing);
 
         match request {
@@ -86,6 +187,274 @@ impl Slides {
         }
     }
 
+    fn start_transition(&mut self, previous_slide: usize) {
+        let effect = [self.current_slide, previous_slide]
+            .iter()
+            .filter_map(|index| match self.slide_cache.peek(index) {
+                Some(Status::Ready(slide)) => slide.transition(),
+                _ => None,
+            })
+            .next();
+
+        self.transition = effect.map(|effect| ActiveTransition {
+            previous_slide,
+            effect,
+            forward: self.current_slide > previous_slide,
+            _timeout: TimeoutService::spawn(
+                TRANSITION_DURATION,
+                self.link.callback(|_| Message::TransitionComplete),
+            ),
+        });
+    }
+
+    /// Lets presenters point at and draw on the slide, with the mouse
+    /// pointer acting as a laser pointer and dragging to draw strokes.
+    fn view_annotation_layer(&self) -> Html {
+        if self.on_annotate.is_none() {
+            return html! {};
+        }
+
+        let layer = self.annotation_layer.clone();
+        let pointer_moved = self.link.callback(move |event: MouseEvent| {
+            Message::PointerMoved(Self::slide_point(&layer, &event))
+        });
+        let layer = self.annotation_layer.clone();
+        let stroke_started = self.link.callback(move |event: MouseEvent| {
+            Message::StrokeStarted(Self::slide_point(&layer, &event))
+        });
+        let stroke_ended = self.link.callback(|_| Message::StrokeEnded);
+        let pointer_left = self.link.callback(|_| Message::PointerLeft);
+        let cleared = self.link.callback(|_| Message::ClearAnnotations);
+
+        html! {
+            <div
+                ref = self.annotation_layer.clone()
+                onmousemove = pointer_moved
+                onmousedown = stroke_started
+                onmouseup = stroke_ended
+                onmouseleave = pointer_left
+                ondblclick = cleared
+                style = "\
+                    position: absolute;\
+                    top: 0;\
+                    left: 0;\
+                    width: 100%;\
+                    height: 100%;\
+                    cursor: crosshair;\
+                "
+                />
+        }
+    }
+
+    /// Normalizes the position of a mouse event to the slide's logical size,
+    /// which the annotation layer covers exactly.
+    fn slide_point(layer: &NodeRef, event: &MouseEvent) -> Option<SlidePoint> {
+        let bounds = layer.cast::<Element>()?.get_bounding_client_rect();
+
+        if bounds.width() <= 0.0 || bounds.height() <= 0.0 {
+            return None;
+        }
+
+        Some(SlidePoint {
+            x: (f64::from(event.client_x()) - bounds.left()) / bounds.width(),
+            y: (f64::from(event.client_y()) - bounds.top()) / bounds.height(),
+        })
+    }
+
+    fn annotate(&self, annotation: Annotation) {
+        if let Some(on_annotate) = &self.on_annotate {
+            on_annotate.emit(annotation);
+        }
+    }
+
+    /// Queues a pointer move, adding the point to the stroke being drawn,
+    /// until the next animation frame.
+    fn queue_pointer(&mut self, point: Option<SlidePoint>) {
+        let pending = &mut self.pending_annotations;
+
+        pending.pointer = Some(point);
+
+        if let (Some(id), Some(point)) = (self.drawing, point) {
+            match &mut pending.stroke {
+                Some((pending_id, points)) if *pending_id == id => {
+                    points.push(point)
+                }
+                _ => pending.stroke = Some((id, vec![point])),
+            }
+        }
+
+        if self.annotation_frame.is_none() {
+            self.annotation_frame =
+                Some(RenderService::request_animation_frame(
+                    self.link.callback(|_| Message::AnnotationFrame),
+                ));
+        }
+    }
+
+    /// Sends the queued pointer move and stroke points.
+    fn flush_annotations(&mut self) {
+        let pending = mem::take(&mut self.pending_annotations);
+
+        self.annotation_frame = None;
+
+        if let Some(point) = pending.pointer {
+            self.annotate(Annotation::Pointer { point });
+        }
+
+        if let Some((id, points)) = pending.stroke {
+            self.annotate(Annotation::Stroke { id, points });
+        }
+    }
+
+    /// Finds the poll option that was clicked, if any.
+    fn poll_vote(event: MouseEvent) -> Option<Message> {
+        let target: Element = event.target()?.dyn_into().ok()?;
+        let option = target.closest("[data-poll-option]").ok()??;
+        let poll = option.closest("[data-poll]").ok()??;
+
+        Some(Message::Vote {
+            poll: poll.get_attribute("data-poll")?.parse().ok()?,
+            option: option.get_attribute("data-poll-option")?.parse().ok()?,
+        })
+    }
+
+    fn vote(&mut self, poll: u32, option: u32) -> ShouldRender {
+        let on_vote = match &self.on_vote {
+            Some(on_vote) => on_vote,
+            None => return false,
+        };
+
+        on_vote.emit((poll, option));
+
+        if let Some(Status::Ready(slide)) =
+            self.slide_cache.peek(&self.current_slide)
+        {
+            slide.mark_vote(poll, option);
+        }
+
+        true
+    }
+
+    fn show_poll_results(&self, slide: &Slide) {
+        for results in self.poll_results.values() {
+            slide.show_poll_results(results);
+        }
+    }
+
+    fn view_step_spec_errors(&self, slide: &Slide) -> Html {
+        html! {
+            <div style=STEP_SPEC_ERRORS_STYLE>
+                <p><strong>{"Invalid step specifications"}</strong></p>
+                <ul>
+                    { for slide.errors().iter().map(|error| html! {
+                        <li>{ error.to_string() }</li>
+                    }) }
+                </ul>
+            </div>
+        }
+    }
+
+    fn view_slide(&self, index: usize) -> Html {
+        let slide_status = self.slide_cache.peek(&index);
+
+        match slide_status {
+            Some(Status::Loading(_)) => html! {
+                <p>{"Loading slide"}</p>
+            },
+            Some(Status::Ready(ref slide)) => match slide.as_node() {
+                Ok(node) if slide.errors().is_empty() => VNode::VRef(node),
+                Ok(node) => html! {
+                    <>
+                        { VNode::VRef(node) }
+                        { self.view_step_spec_errors(slide) }
+                    </>
+                },
+                Err(error) => html! {
+                    <div>
+                        <p><strong>
+                            {"Failed to animate slide"}
+                        </strong></p>
+                        <p>{format!("Error: {}", error)}</p>
+                    </div>
+                },
+            },
+            Some(Status::Error {
+                description,
+                ref cause,
+                ref contents,
+            }) => {
+                html! {
+                    <div>
+                        <p><strong>{description}</strong></p>
+                        {
+                            if let Some(cause) = cause {
+                                html! {
+                                    <p>{
+                                        format!("Error: {}", cause)
+                                    }</p>
+                                }
+                            } else {
+                                html!{}
+                            }
+                        }
+                        {
+                            if let Some(contents) = contents {
+                                html! {
+                                    <p>{ format!(
+                                        "Contents: {}",
+                                        contents,
+                                    )}</p>
+                                }
+                            } else {
+                                html!{}
+                            }
+                        }
+                    </div>
+                }
+            }
+            None => {
+                html! {
+                    <div>
+                        <p><strong>{"Fatal error"}</strong></p>
+                        <p>{"Slide was not loaded"}</p>
+                    </div>
+                }
+            }
+        }
+    }
+
+    fn view_transition(&self, transition: &ActiveTransition) -> Html {
+        let classes = format!(
+            "slide-layer slide-transition-{} slide-{}",
+            transition.effect.name(),
+            direction_class(transition.forward),
+        );
+
+        html! {
+            <>
+                {
+                    // The previous slide may have been evicted from the cache
+                    // while loading the new one, and then there's nothing to
+                    // show leaving.
+                    if let Some(Status::Ready(_)) =
+                        self.slide_cache.peek(&transition.previous_slide)
+                    {
+                        html! {
+                            <div class={ format!("{} slide-leave", classes) }>
+                                { self.view_slide(transition.previous_slide) }
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+                <div class={ format!("{} slide-enter", classes) }>
+                    { self.view_slide(self.current_slide) }
+                </div>
+            </>
+        }
+    }
+
     fn animate_slide(&mut self) {
         let current_slide = self.slide_cache.get_mut(&self.current_slide);
 
@@ -100,24 +469,32 @@ impl Component for Slides {
     type Message = Message;
 
     fn create(properties: Self::Properties, link: ComponentLink<Self>) -> Self {
-        let locale_path = properties
-            .locale
-            .map(|mut locale| {
-                locale.push('/');
-                locale
-            })
-            .unwrap_or_else(String::new);
+        styles::inject("presentrs-transitions", TRANSITIONS_CSS);
+        styles::inject("presentrs-polls", POLLS_CSS);
 
         let mut this = Slides {
-            locale_path,
+            locale_path: Self::locale_path(properties.locale),
             slide_cache: LruCache::new(10),
             current_slide: properties.current_slide,
             current_step: properties.current_step,
             link,
             size: properties.size,
             on_slide_loaded: properties.on_slide_loaded,
+            on_manifest_loaded: properties.on_manifest_loaded,
+            manifest_task: None,
+            transition: None,
+            annotations: properties.annotations,
+            on_annotate: properties.on_annotate,
+            annotation_layer: NodeRef::default(),
+            drawing: None,
+            next_stroke_id: 0,
+            pending_annotations: PendingAnnotations::default(),
+            annotation_frame: None,
+            poll_results: properties.poll_results,
+            on_vote: properties.on_vote,
         };
 
+        this.fetch_manifest();
         this.fetch_slides();
         this
     }
@@ -128,7 +505,9 @@ impl Component for Slides {
                 match Slide::from_html(contents.trim()) {
                     Ok(slide) => {
                         let num_steps = slide.num_steps();
+                        let summary = slide.summary().clone();
 
+                        self.show_poll_results(&slide);
                         self.slide_cache.put(index, Status::Ready(slide));
 
                         if index == self.current_slide {
@@ -136,7 +515,7 @@ impl Component for Slides {
                         }
 
                         if let Some(ref callback) = self.on_slide_loaded {
-                            callback.emit((index, num_steps));
+                            callback.emit((index, num_steps, summary));
                         }
                     }
                     Err(error) => {
@@ -161,6 +540,61 @@ impl Component for Slides {
                     },
                 );
             }
+            Message::ManifestLoaded(manifest) => {
+                self.manifest_task = None;
+
+                if let (Some(manifest), Some(callback)) =
+                    (manifest, self.on_manifest_loaded.as_ref())
+                {
+                    callback.emit(manifest);
+                }
+
+                return false;
+            }
+            Message::TransitionComplete => {
+                self.transition = None;
+            }
+            Message::PointerMoved(point) => {
+                self.queue_pointer(point);
+                return false;
+            }
+            Message::AnnotationFrame => {
+                self.flush_annotations();
+                return false;
+            }
+            Message::PointerLeft => {
+                self.pending_annotations.pointer = Some(None);
+                self.flush_annotations();
+                self.drawing = None;
+                return false;
+            }
+            Message::StrokeStarted(point) => {
+                self.flush_annotations();
+
+                if let Some(point) = point {
+                    let id = self.next_stroke_id;
+
+                    self.next_stroke_id = id.wrapping_add(1);
+                    self.drawing = Some(id);
+                    self.annotate(Annotation::Stroke {
+                        id,
+                        points: vec![point],
+                    });
+                }
+
+                return false;
+            }
+            Message::StrokeEnded => {
+                self.flush_annotations();
+                self.drawing = None;
+                return false;
+            }
+            Message::ClearAnnotations => {
+                self.flush_annotations();
+                self.annotate(Annotation::Clear);
+                return false;
+            }
+            Message::Vote { poll, option } => return self.vote(poll, option),
         };
 
         true
@@ -168,14 +602,55 @@ impl Component for Slides {
 
     fn change(&mut self, properties: Self::Properties) -> ShouldRender {
         self.size = properties.size;
+        self.annotations = properties.annotations;
+        self.on_annotate = properties.on_annotate;
+        self.on_vote = properties.on_vote;
+
+        if self.on_annotate.is_none() {
+            self.drawing = None;
+            self.pending_annotations = PendingAnnotations::default();
+            self.annotation_frame = None;
+        }
+
+        if !Rc::ptr_eq(&self.poll_results, &properties.poll_results) {
+            self.poll_results = properties.poll_results;
+
+            for (_, status) in self.slide_cache.iter() {
+                if let Status::Ready(slide) = status {
+                    self.show_poll_results(slide);
+                }
+            }
+        }
+
+        let locale_path = Self::locale_path(properties.locale);
+        let slide_changed = self.current_slide != properties.current_slide;
+        let step_changed = self.current_step != properties.current_step;
 
-        if self.current_slide != properties.current_slide {
+        self.current_step = properties.current_step;
+
+        if self.locale_path != locale_path {
+            self.locale_path = locale_path;
             self.current_slide = properties.current_slide;
+            self.transition = None;
+            self.slide_cache.clear();
+            self.fetch_manifest();
+            self.fetch_slides();
+        } else if slide_changed {
+            let previous_slide = self.current_slide;
+
+            self.current_slide = properties.current_slide;
+
+            if let Some(Status::Ready(slide)) =
+                self.slide_cache.get_mut(&self.current_slide)
+            {
+                slide.reset_animation();
+            }
+
+            self.start_transition(previous_slide);
             self.fetch_slides();
         }
 
-        if self.current_step != properties.current_step {
-            self.current_step = properties.current_step;
+        if slide_changed || step_changed {
             self.animate_slide();
         }
 
@@ -183,83 +658,34 @@ impl Component for Slides {
     }
 
     fn view(&self) -> Html {
-        let slide_status = self.slide_cache.peek(&self.current_slide);
+        let vote = self.link.batch_callback(Self::poll_vote);
 
         html! {
             <div
                 id={"slide"}
-                style={ self.size.to_string() }
+                // Gestures on the slide are handled by the presentation, but
+                // the notes and panels around it keep scrolling natively.
+                style={ format!("{} touch-action: none;", self.size) }
                 class={ format!("current-slide-step-{}", self.current_step) }
+                onclick = vote
             >
                 {
-                    match slide_status {
-                        Some(Status::Loading(_)) => html! {
-                            <p>{"Loading slide"}</p>
-                        },
-                        Some(Status::Ready(ref slide)) => {
-                            match slide.as_node() {
-                                Ok(node) => VNode::VRef(node),
-                                Err(error) => html! {
-                                    <div>
-                                        <p><strong>
-                                            {"Failed to animate slide"}
-                                        </strong></p>
-                                        <p>{format!("Error: {}", error)}</p>
-                                    </div>
-                                },
-                            }
-                        }
-                        Some(Status::Error {
-                            description,
-                            ref cause,
-                            ref contents,
-                        }) => {
-                            html! {
-                                <div>
-                                    <p><strong>{description}</strong></p>
-                                    {
-                                        if let Some(cause) = cause {
-                                            html! {
-                                                <p>{
-                                                    format!("Error: {}", cause)
-                                                }</p>
-                                            }
-                                        } else {
-                                            html!{}
-                                        }
-                                    }
-                                    {
-                                        if let Some(contents) = contents {
-                                            html! {
-                                                <p>{ format!(
-                                                    "Contents: {}",
-                                                    contents,
-                                                )}</p>
-                                            }
-                                        } else {
-                                            html!{}
-                                        }
-                                    }
-                                </div>
-                            }
-                        }
-                        None => {
-                            html! {
-                                <div>
-                                    <p><strong>{"Fatal error"}</strong></p>
-                                    <p>{"Slide was not loaded"}</p>
-                                </div>
-                            }
+                    match self.transition {
+                        Some(ref transition) => {
+                            self.view_transition(transition)
                         }
+                        None => self.view_slide(self.current_slide),
                     }
                 }
+                { self.annotations.view(&self.size) }
+                { self.view_annotation_layer() }
             </div>
         }
     }
 }
 
 pub enum Status {
-    Loading(FetchTask),
+    Loading(#[allow(dead_code)] FetchTask),
     Ready(Slide),
     Error {
         description: &'static str,
@@ -279,7 +705,18 @@ pub struct Properties {
     #[prop_or_default]
     pub size: SlideSize,
     #[prop_or_default]
-    pub on_slide_loaded: Option<Callback<(usize, usize)>>,
+    pub on_slide_loaded: Option<Callback<(usize, usize, SlideSummary)>>,
+    #[prop_or_default]
+    pub on_manifest_loaded: Option<Callback<Manifest>>,
+    #[prop_or_default]
+    pub annotations: Rc<Annotations>,
+    #[prop_or_default]
+    pub on_annotate: Option<Callback<Annotation>>,
+    #[prop_or_default]
+    pub poll_results: Rc<BTreeMap<u32, PollResults>>,
+    /// Receives the polls and options the audience votes for.
+    #[prop_or_default]
+    pub on_vote: Option<Callback<(u32, u32)>>,
 }
 
 impl Default for Properties {
@@ -290,10 +727,24 @@ impl Default for Properties {
             current_step: 1,
             size: SlideSize::default(),
             on_slide_loaded: None,
+            on_manifest_loaded: None,
+            annotations: Rc::default(),
+            on_annotate: None,
+            poll_results: Rc::default(),
+            on_vote: None,
         }
     }
 }
 
 pub enum Message {
     LoadComplete(usize, Result<String, String>),
+    ManifestLoaded(Option<Manifest>),
+    TransitionComplete,
+    PointerMoved(Option<SlidePoint>),
+    AnnotationFrame,
+    PointerLeft,
+    StrokeStarted(Option<SlidePoint>),
+    StrokeEnded,
+    ClearAnnotations,
+    Vote { poll: u32, option: u32 },
 }
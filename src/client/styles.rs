@@ -0,0 +1,22 @@
+/// Adds a stylesheet to the page's head, unless one with the same ID was
+/// already added, so that components don't render it again on every update.
+pub fn inject(id: &str, css: &str) {
+    let document = match web_sys::window().and_then(|window| window.document())
+    {
+        Some(document) => document,
+        None => return,
+    };
+
+    if document.get_element_by_id(id).is_some() {
+        return;
+    }
+
+    let head = document.query_selector("head").ok().flatten();
+
+    if let (Ok(style), Some(head)) = (document.create_element("style"), head) {
+        style.set_id(id);
+        style.set_text_content(Some(css));
+
+        let _ = head.append_child(&style);
+    }
+}
@@ -0,0 +1,243 @@
+const SWIPE_DISTANCE: f64 = 50.0;
+const TAP_DISTANCE: f64 = 10.0;
+const TAP_DURATION: f64 = 300.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TouchPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gesture {
+    Swipe(SwipeDirection),
+    Tap(TouchPoint),
+    Pinch(f64),
+    Pan { delta_x: f64, delta_y: f64 },
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TouchTracker {
+    start: Option<(TouchPoint, f64)>,
+    last: Option<TouchPoint>,
+    pinch_distance: Option<f64>,
+    pinching: bool,
+}
+
+impl TouchPoint {
+    fn distance_to(&self, other: &TouchPoint) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+}
+
+impl TouchTracker {
+    pub fn start(&mut self, touches: &[TouchPoint], time: f64) {
+        match touches {
+            [first, second, ..] => {
+                self.pinch_distance = Some(first.distance_to(second));
+                self.pinching = true;
+                self.start = None;
+            }
+            [touch] if !self.pinching => {
+                self.start = Some((*touch, time));
+                self.last = Some(*touch);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn move_to(
+        &mut self,
+        touches: &[TouchPoint],
+        panning: bool,
+    ) -> Option<Gesture> {
+        match touches {
+            [first, second, ..] => {
+                let distance = first.distance_to(second);
+                let previous = self.pinch_distance.replace(distance)?;
+
+                if previous > 0.0 {
+                    Some(Gesture::Pinch(distance / previous))
+                } else {
+                    None
+                }
+            }
+            [touch] if !self.pinching => {
+                let last = self.last.replace(*touch)?;
+
+                if panning {
+                    Some(Gesture::Pan {
+                        delta_x: touch.x - last.x,
+                        delta_y: touch.y - last.y,
+                    })
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    pub fn end(
+        &mut self,
+        remaining_touches: usize,
+        time: f64,
+        panning: bool,
+    ) -> Option<Gesture> {
+        if remaining_touches > 0 {
+            self.pinch_distance = None;
+            return None;
+        }
+
+        let start = self.start.take();
+        let last = self.last.take();
+
+        self.pinch_distance = None;
+
+        if std::mem::replace(&mut self.pinching, false) {
+            return None;
+        }
+
+        let ((start, start_time), last) = start.zip(last)?;
+        let delta_x = last.x - start.x;
+        let delta_y = last.y - start.y;
+
+        if delta_x.abs() < TAP_DISTANCE
+            && delta_y.abs() < TAP_DISTANCE
+            && time - start_time < TAP_DURATION
+        {
+            Some(Gesture::Tap(start))
+        } else if panning {
+            None
+        } else if delta_x.abs() >= delta_y.abs()
+            && delta_x.abs() >= SWIPE_DISTANCE
+        {
+            if delta_x < 0.0 {
+                Some(Gesture::Swipe(SwipeDirection::Left))
+            } else {
+                Some(Gesture::Swipe(SwipeDirection::Right))
+            }
+        } else if delta_y.abs() >= SWIPE_DISTANCE {
+            if delta_y < 0.0 {
+                Some(Gesture::Swipe(SwipeDirection::Up))
+            } else {
+                Some(Gesture::Swipe(SwipeDirection::Down))
+            }
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Gesture, SwipeDirection, TouchPoint, TouchTracker};
+
+    fn point(x: f64, y: f64) -> TouchPoint {
+        TouchPoint { x, y }
+    }
+
+    fn swipe(from: TouchPoint, to: TouchPoint) -> Option<Gesture> {
+        let mut tracker = TouchTracker::default();
+
+        tracker.start(&[from], 0.0);
+        tracker.move_to(&[to], false);
+        tracker.end(0, 100.0, false)
+    }
+
+    #[test]
+    fn a_short_touch_is_a_tap() {
+        let mut tracker = TouchTracker::default();
+
+        tracker.start(&[point(10.0, 20.0)], 0.0);
+        tracker.move_to(&[point(12.0, 21.0)], false);
+
+        assert_eq!(
+            tracker.end(0, 100.0, false),
+            Some(Gesture::Tap(point(10.0, 20.0))),
+        );
+    }
+
+    #[test]
+    fn a_long_touch_is_not_a_tap() {
+        let mut tracker = TouchTracker::default();
+
+        tracker.start(&[point(10.0, 20.0)], 0.0);
+
+        assert_eq!(tracker.end(0, 1000.0, false), None);
+    }
+
+    #[test]
+    fn swipes_follow_the_larger_movement() {
+        let origin = point(100.0, 100.0);
+
+        assert_eq!(
+            swipe(origin, point(20.0, 90.0)),
+            Some(Gesture::Swipe(SwipeDirection::Left)),
+        );
+        assert_eq!(
+            swipe(origin, point(180.0, 110.0)),
+            Some(Gesture::Swipe(SwipeDirection::Right)),
+        );
+        assert_eq!(
+            swipe(origin, point(90.0, 20.0)),
+            Some(Gesture::Swipe(SwipeDirection::Up)),
+        );
+        assert_eq!(
+            swipe(origin, point(110.0, 180.0)),
+            Some(Gesture::Swipe(SwipeDirection::Down)),
+        );
+    }
+
+    #[test]
+    fn short_movements_are_not_swipes() {
+        assert_eq!(swipe(point(100.0, 100.0), point(130.0, 100.0)), None);
+    }
+
+    #[test]
+    fn pinching_reports_the_change_in_distance() {
+        let mut tracker = TouchTracker::default();
+
+        tracker.start(&[point(0.0, 0.0), point(100.0, 0.0)], 0.0);
+
+        assert_eq!(
+            tracker.move_to(&[point(0.0, 0.0), point(200.0, 0.0)], false),
+            Some(Gesture::Pinch(2.0)),
+        );
+    }
+
+    #[test]
+    fn lifting_fingers_after_a_pinch_is_not_a_tap() {
+        let mut tracker = TouchTracker::default();
+
+        tracker.start(&[point(0.0, 0.0), point(100.0, 0.0)], 0.0);
+        tracker.start(&[point(0.0, 0.0)], 10.0);
+
+        assert_eq!(tracker.end(1, 20.0, false), None);
+        assert_eq!(tracker.end(0, 30.0, false), None);
+    }
+
+    #[test]
+    fn dragging_while_zoomed_pans_instead_of_swiping() {
+        let mut tracker = TouchTracker::default();
+
+        tracker.start(&[point(100.0, 100.0)], 0.0);
+
+        assert_eq!(
+            tracker.move_to(&[point(40.0, 90.0)], true),
+            Some(Gesture::Pan {
+                delta_x: -60.0,
+                delta_y: -10.0,
+            }),
+        );
+        assert_eq!(tracker.end(0, 100.0, true), None);
+    }
+}
@@ -0,0 +1,195 @@
+use std::{str::FromStr, time::Duration};
+
+pub const TRANSITION_DURATION: Duration = Duration::from_millis(500);
+
+pub const TRANSITIONS_CSS: &str = "
+    .slide-layer {
+        position: absolute;
+        top: 0;
+        left: 0;
+        width: 100%;
+        height: 100%;
+    }
+
+    .slide-enter.slide-transition-fade {
+        animation: presentrs-fade-in 0.4s both;
+    }
+    .slide-leave.slide-transition-fade {
+        animation: presentrs-fade-out 0.4s both;
+    }
+    .slide-enter.slide-transition-slide.slide-forward {
+        animation: presentrs-enter-from-right 0.4s both;
+    }
+    .slide-enter.slide-transition-slide.slide-backward {
+        animation: presentrs-enter-from-left 0.4s both;
+    }
+    .slide-leave.slide-transition-slide.slide-forward {
+        animation: presentrs-leave-to-left 0.4s both;
+    }
+    .slide-leave.slide-transition-slide.slide-backward {
+        animation: presentrs-leave-to-right 0.4s both;
+    }
+    .slide-enter.slide-transition-zoom.slide-forward {
+        animation: presentrs-zoom-in-from-small 0.4s both;
+    }
+    .slide-enter.slide-transition-zoom.slide-backward {
+        animation: presentrs-zoom-in-from-large 0.4s both;
+    }
+    .slide-leave.slide-transition-zoom.slide-forward {
+        animation: presentrs-zoom-out-to-large 0.4s both;
+    }
+    .slide-leave.slide-transition-zoom.slide-backward {
+        animation: presentrs-zoom-out-to-small 0.4s both;
+    }
+
+    .step-effect-fade-in.step-entering {
+        animation: presentrs-fade-in 0.4s both;
+    }
+    .step-effect-fade-in.step-leaving {
+        animation: presentrs-fade-out 0.4s both;
+    }
+    .step-effect-fly-in.step-entering.step-forward {
+        animation: presentrs-fly-in-from-below 0.4s both;
+    }
+    .step-effect-fly-in.step-entering.step-backward {
+        animation: presentrs-fly-in-from-above 0.4s both;
+    }
+    .step-effect-highlight {
+        transition: background-color 0.4s;
+    }
+    .step-effect-highlight.active-in-slide-step {
+        background-color: yellow;
+    }
+    .step-effect-highlight.inactive-in-slide-step {
+        visibility: visible;
+    }
+    .step-effect-strike-out.step-past {
+        visibility: visible;
+        text-decoration: line-through;
+        opacity: 0.6;
+    }
+
+    @keyframes presentrs-fade-in {
+        from { opacity: 0; }
+        to { opacity: 1; }
+    }
+    @keyframes presentrs-fade-out {
+        from { opacity: 1; }
+        to { opacity: 0; }
+    }
+    @keyframes presentrs-enter-from-right {
+        from { transform: translateX(100%); }
+        to { transform: translateX(0); }
+    }
+    @keyframes presentrs-enter-from-left {
+        from { transform: translateX(-100%); }
+        to { transform: translateX(0); }
+    }
+    @keyframes presentrs-leave-to-left {
+        from { transform: translateX(0); }
+        to { transform: translateX(-100%); }
+    }
+    @keyframes presentrs-leave-to-right {
+        from { transform: translateX(0); }
+        to { transform: translateX(100%); }
+    }
+    @keyframes presentrs-zoom-in-from-small {
+        from { transform: scale(0.5); opacity: 0; }
+        to { transform: scale(1); opacity: 1; }
+    }
+    @keyframes presentrs-zoom-in-from-large {
+        from { transform: scale(1.5); opacity: 0; }
+        to { transform: scale(1); opacity: 1; }
+    }
+    @keyframes presentrs-zoom-out-to-large {
+        from { transform: scale(1); opacity: 1; }
+        to { transform: scale(1.5); opacity: 0; }
+    }
+    @keyframes presentrs-zoom-out-to-small {
+        from { transform: scale(1); opacity: 1; }
+        to { transform: scale(0.5); opacity: 0; }
+    }
+    @keyframes presentrs-fly-in-from-below {
+        from { transform: translateY(50px); opacity: 0; }
+        to { transform: translateY(0); opacity: 1; }
+    }
+    @keyframes presentrs-fly-in-from-above {
+        from { transform: translateY(-50px); opacity: 0; }
+        to { transform: translateY(0); opacity: 1; }
+    }
+";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SlideTransition {
+    Fade,
+    Slide,
+    Zoom,
+}
+
+impl SlideTransition {
+    pub const ATTRIBUTE: &'static str = "data-slide-transition";
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SlideTransition::Fade => "fade",
+            SlideTransition::Slide => "slide",
+            SlideTransition::Zoom => "zoom",
+        }
+    }
+}
+
+impl FromStr for SlideTransition {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.trim() {
+            "fade" => Ok(SlideTransition::Fade),
+            "slide" => Ok(SlideTransition::Slide),
+            "zoom" => Ok(SlideTransition::Zoom),
+            name => Err(format!("Unknown slide transition: {}", name)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StepEffect {
+    FadeIn,
+    FlyIn,
+    Highlight,
+    StrikeOut,
+}
+
+impl StepEffect {
+    pub const ATTRIBUTE: &'static str = "data-step-effect";
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StepEffect::FadeIn => "fade-in",
+            StepEffect::FlyIn => "fly-in",
+            StepEffect::Highlight => "highlight",
+            StepEffect::StrikeOut => "strike-out",
+        }
+    }
+}
+
+impl FromStr for StepEffect {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.trim() {
+            "fade-in" => Ok(StepEffect::FadeIn),
+            "fly-in" => Ok(StepEffect::FlyIn),
+            "highlight" => Ok(StepEffect::Highlight),
+            "strike-out" => Ok(StepEffect::StrikeOut),
+            name => Err(format!("Unknown step effect: {}", name)),
+        }
+    }
+}
+
+pub fn direction_class(forward: bool) -> &'static str {
+    if forward {
+        "forward"
+    } else {
+        "backward"
+    }
+}
@@ -0,0 +1,152 @@
+use {
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::BTreeMap,
+        convert::TryFrom,
+        fmt::{self, Display, Formatter},
+        str::FromStr,
+    },
+};
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct DeckConfig {
+    #[serde(default)]
+    pub key_bindings: BTreeMap<String, String>,
+    #[serde(default)]
+    pub slide_format: Option<SlideFormat>,
+    #[serde(default)]
+    pub slide_fit: Option<SlideFit>,
+    #[serde(default)]
+    pub playback: Option<PlaybackConfig>,
+    /// How long each slide should take to present, in seconds, by slide
+    /// number.
+    #[serde(default)]
+    pub time_targets: BTreeMap<usize, f64>,
+}
+
+impl DeckConfig {
+    pub const FILE_NAME: &'static str = "deck.json";
+}
+
+/// Replays a recorded talk, following its timeline instead of a presenter.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PlaybackConfig {
+    /// The URL of the timeline file, relative to the deck.
+    pub timeline: String,
+    /// A CSS selector for the `<video>` or `<audio>` element with the
+    /// recording, whose current time the slides follow.
+    #[serde(default)]
+    pub media: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct SlideFormat {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl SlideFormat {
+    pub const STANDARD: SlideFormat = SlideFormat {
+        width: 800.0,
+        height: 600.0,
+    };
+    pub const WIDESCREEN: SlideFormat = SlideFormat {
+        width: 960.0,
+        height: 540.0,
+    };
+    pub const WIDESCREEN_16_10: SlideFormat = SlideFormat {
+        width: 960.0,
+        height: 600.0,
+    };
+
+    const CUSTOM_RATIO_HEIGHT: f64 = 600.0;
+
+    pub fn new(width: f64, height: f64) -> Result<Self, String> {
+        if width.is_finite()
+            && height.is_finite()
+            && width > 0.0
+            && height > 0.0
+        {
+            Ok(SlideFormat { width, height })
+        } else {
+            Err(format!("Invalid slide size: {}x{}", width, height))
+        }
+    }
+
+    pub fn from_aspect_ratio(width: f64, height: f64) -> Result<Self, String> {
+        let ratio = width / height;
+
+        Self::new(
+            (Self::CUSTOM_RATIO_HEIGHT * ratio).round(),
+            Self::CUSTOM_RATIO_HEIGHT,
+        )
+    }
+
+    fn parse_pair(spec: &str, separator: char) -> Option<(f64, f64)> {
+        let mut parts = spec.splitn(2, separator);
+        let first = parts.next()?.trim().parse().ok()?;
+        let second = parts.next()?.trim().parse().ok()?;
+
+        Some((first, second))
+    }
+}
+
+impl Default for SlideFormat {
+    fn default() -> Self {
+        SlideFormat::STANDARD
+    }
+}
+
+impl FromStr for SlideFormat {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        match spec.trim() {
+            "4:3" => Ok(SlideFormat::STANDARD),
+            "16:9" => Ok(SlideFormat::WIDESCREEN),
+            "16:10" => Ok(SlideFormat::WIDESCREEN_16_10),
+            spec => {
+                if let Some((width, height)) = Self::parse_pair(spec, 'x') {
+                    Self::new(width, height)
+                } else if let Some((width, height)) =
+                    Self::parse_pair(spec, ':')
+                {
+                    Self::from_aspect_ratio(width, height)
+                } else {
+                    Err(format!("Invalid slide format: {}", spec))
+                }
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for SlideFormat {
+    type Error = String;
+
+    fn try_from(spec: String) -> Result<Self, Self::Error> {
+        spec.parse()
+    }
+}
+
+impl From<SlideFormat> for String {
+    fn from(format: SlideFormat) -> Self {
+        format.to_string()
+    }
+}
+
+impl Display for SlideFormat {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}x{}", self.width, self.height)
+    }
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum SlideFit {
+    #[default]
+    Letterbox,
+    Fill,
+}
@@ -0,0 +1,254 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum KeyAction {
+    PreviousStep,
+    NextStep,
+    PreviousSlide,
+    NextSlide,
+    FirstSlide,
+    LastSlide,
+    JumpToTypedSlide,
+    Search,
+    ToggleNotes,
+    TogglePresent,
+    BlankScreen,
+    ToggleFullscreen,
+    Cancel,
+    ToggleHelp,
+    React,
+}
+
+impl KeyAction {
+    const ALL: [KeyAction; 15] = [
+        KeyAction::PreviousStep,
+        KeyAction::NextStep,
+        KeyAction::PreviousSlide,
+        KeyAction::NextSlide,
+        KeyAction::FirstSlide,
+        KeyAction::LastSlide,
+        KeyAction::JumpToTypedSlide,
+        KeyAction::Search,
+        KeyAction::ToggleNotes,
+        KeyAction::TogglePresent,
+        KeyAction::BlankScreen,
+        KeyAction::ToggleFullscreen,
+        KeyAction::Cancel,
+        KeyAction::ToggleHelp,
+        KeyAction::React,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyAction::PreviousStep => "previous-step",
+            KeyAction::NextStep => "next-step",
+            KeyAction::PreviousSlide => "previous-slide",
+            KeyAction::NextSlide => "next-slide",
+            KeyAction::FirstSlide => "first-slide",
+            KeyAction::LastSlide => "last-slide",
+            KeyAction::JumpToTypedSlide => "jump-to-typed-slide",
+            KeyAction::Search => "search",
+            KeyAction::ToggleNotes => "toggle-notes",
+            KeyAction::TogglePresent => "toggle-present",
+            KeyAction::BlankScreen => "blank-screen",
+            KeyAction::ToggleFullscreen => "toggle-fullscreen",
+            KeyAction::Cancel => "cancel",
+            KeyAction::ToggleHelp => "toggle-help",
+            KeyAction::React => "react",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            KeyAction::PreviousStep => "Previous step",
+            KeyAction::NextStep => "Next step",
+            KeyAction::PreviousSlide => "Previous slide",
+            KeyAction::NextSlide => "Next slide",
+            KeyAction::FirstSlide => "First slide",
+            KeyAction::LastSlide => "Last slide",
+            KeyAction::JumpToTypedSlide => "Go to the typed slide number",
+            KeyAction::Search => "Search slides",
+            KeyAction::ToggleNotes => "Show or hide notes",
+            KeyAction::TogglePresent => "Start or stop presenting",
+            KeyAction::BlankScreen => "Blank the screen",
+            KeyAction::ToggleFullscreen => "Enter or leave fullscreen",
+            KeyAction::Cancel => "Cancel input or unblank the screen",
+            KeyAction::ToggleHelp => "Show or hide this help",
+            KeyAction::React => "Applaud the presenter",
+        }
+    }
+}
+
+impl Display for KeyAction {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.name())
+    }
+}
+
+impl FromStr for KeyAction {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        KeyAction::ALL
+            .iter()
+            .copied()
+            .find(|action| action.name() == name)
+            .ok_or_else(|| format!("Unknown key binding action: {}", name))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeyBindings {
+    bindings: BTreeMap<String, KeyAction>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let bindings = [
+            ("ArrowLeft", KeyAction::PreviousStep),
+            ("PageUp", KeyAction::PreviousStep),
+            ("Backspace", KeyAction::PreviousStep),
+            ("ArrowRight", KeyAction::NextStep),
+            ("PageDown", KeyAction::NextStep),
+            (" ", KeyAction::NextStep),
+            ("ArrowUp", KeyAction::PreviousSlide),
+            ("ArrowDown", KeyAction::NextSlide),
+            ("Home", KeyAction::FirstSlide),
+            ("F5", KeyAction::ToggleFullscreen),
+            ("End", KeyAction::LastSlide),
+            ("Enter", KeyAction::JumpToTypedSlide),
+            ("/", KeyAction::Search),
+            ("n", KeyAction::ToggleNotes),
+            ("p", KeyAction::TogglePresent),
+            ("b", KeyAction::BlankScreen),
+            (".", KeyAction::BlankScreen),
+            ("f", KeyAction::ToggleFullscreen),
+            ("Escape", KeyAction::Cancel),
+            ("?", KeyAction::ToggleHelp),
+            ("r", KeyAction::React),
+        ];
+
+        KeyBindings {
+            bindings: bindings
+                .iter()
+                .map(|&(key, action)| (key.to_owned(), action))
+                .collect(),
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn empty() -> Self {
+        KeyBindings {
+            bindings: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_binding(
+        mut self,
+        key: impl Into<String>,
+        action: KeyAction,
+    ) -> Self {
+        self.bind(key, action);
+        self
+    }
+
+    pub fn bind(
+        &mut self,
+        key: impl Into<String>,
+        action: KeyAction,
+    ) -> &mut Self {
+        self.bindings.insert(key.into(), action);
+        self
+    }
+
+    pub fn unbind(&mut self, key: &str) -> &mut Self {
+        self.bindings.remove(key);
+        self
+    }
+
+    /// Applies the bindings configured in `deck.json`, where an empty action
+    /// or `none` unbinds the key.
+    ///
+    /// Entries with unknown actions are skipped, and an error is returned for
+    /// each of them.
+    pub fn apply_config(
+        &mut self,
+        config: &BTreeMap<String, String>,
+    ) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        for (key, action_name) in config {
+            if action_name.is_empty() || action_name == "none" {
+                self.unbind(key);
+            } else {
+                match action_name.parse() {
+                    Ok(action) => {
+                        self.bind(key.clone(), action);
+                    }
+                    Err(error) => {
+                        errors.push(format!("{} (bound to {:?})", error, key))
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+
+    pub fn action_for(&self, key: &str) -> Option<KeyAction> {
+        self.bindings.get(key).copied()
+    }
+
+    pub fn keys_by_action(&self) -> BTreeMap<KeyAction, Vec<&str>> {
+        let mut keys_by_action: BTreeMap<_, Vec<_>> = BTreeMap::new();
+
+        for (key, action) in &self.bindings {
+            keys_by_action
+                .entry(*action)
+                .or_default()
+                .push(key.as_str());
+        }
+
+        keys_by_action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{KeyAction, KeyBindings},
+        std::collections::BTreeMap,
+    };
+
+    fn config(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|&(key, action)| (key.to_owned(), action.to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn applies_the_valid_entries_of_a_config_with_invalid_ones() {
+        let mut key_bindings = KeyBindings::default();
+        let errors = key_bindings.apply_config(&config(&[
+            ("j", "next-step"),
+            ("k", "jump-around"),
+            ("b", "none"),
+        ]));
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("jump-around"));
+        assert_eq!(key_bindings.action_for("j"), Some(KeyAction::NextStep));
+        assert_eq!(key_bindings.action_for("k"), None);
+        assert_eq!(key_bindings.action_for("b"), None);
+        assert_eq!(
+            key_bindings.action_for("ArrowRight"),
+            Some(KeyAction::NextStep),
+        );
+    }
+}
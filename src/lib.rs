@@ -1,11 +1,44 @@
-#[cfg(target_family = "wasm")]
-mod client;
+#[cfg(feature = "client")]
+pub mod client;
 
-#[cfg(not(target_family = "wasm"))]
-mod server;
+#[cfg(feature = "server")]
+pub mod server;
 
-#[cfg(target_family = "wasm")]
+mod config;
+mod key_bindings;
+mod manifest;
+mod navigator;
+mod step_spec;
+mod sync_message;
+mod timeline;
+
+#[cfg(feature = "pipeline")]
+mod pipeline;
+
+pub use crate::{
+    config::{DeckConfig, PlaybackConfig, SlideFit, SlideFormat},
+    key_bindings::{KeyAction, KeyBindings},
+    manifest::{Manifest, SlideSummary},
+    navigator::Navigator,
+    step_spec::{
+        StepBound, StepContext, StepItem, StepRange, StepSpec, StepSpecError,
+        StepSpecErrorKind, LABEL_ATTRIBUTE, STEPS_ATTRIBUTE,
+    },
+    sync_message::{
+        Annotation, ClientMessage, PollResults, Question, Reaction, Role,
+        RosterSummary, ServerMessage, SlidePoint, SyncPosition,
+    },
+    timeline::{Timeline, TimelineEntry},
+};
+
+#[cfg(all(feature = "client", target_family = "wasm"))]
 pub use crate::client::{Presentrs, Properties};
 
-#[cfg(not(target_family = "wasm"))]
-pub use crate::server::{Notes, NotesError, Presentrs, Slides, SlidesError};
+#[cfg(all(feature = "server", not(target_family = "wasm")))]
+pub use crate::server::Presentrs;
+
+#[cfg(feature = "pipeline")]
+pub use crate::pipeline::{
+    Diagnostic, DiagnosticKind, LintOptions, LintReport, Location, Notes,
+    NotesError, Severity, Slides, SlidesError, SlidesIter,
+};
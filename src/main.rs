@@ -0,0 +1,312 @@
+use {
+    clap::{App, AppSettings, Arg, ArgMatches, SubCommand},
+    presentrs::{
+        server::{Presentrs, SlidePresenter, SyncOptions},
+        DeckConfig, LintOptions, LintReport, Notes, Slides,
+    },
+    std::{
+        error::Error,
+        fs,
+        net::SocketAddr,
+        path::{Path, PathBuf},
+        process,
+    },
+};
+
+#[tokio::main]
+async fn main() {
+    let matches = App::new("presentrs")
+        .about("Builds, checks and serves presentations from markdown notes")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name("build")
+                .about("Generates the notes and slides of a deck")
+                .arg(Arg::with_name("NOTES").required(true))
+                .arg(Arg::with_name("OUTPUT").required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("lint")
+                .about(
+                    "Checks decks for problems, comparing the slides of \
+                     several locales if more than one is given",
+                )
+                .arg(Arg::with_name("NOTES").required(true).multiple(true))
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .takes_value(true)
+                        .possible_values(&["text", "json"])
+                        .default_value("text"),
+                )
+                .arg(
+                    Arg::with_name("max-steps")
+                        .long("max-steps")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("config")
+                        .long("config")
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .help("Also checks a deck.json configuration file"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("serve")
+                .about(
+                    "Serves a built deck directory, or builds a deck from \
+                     markdown notes on request",
+                )
+                .arg(Arg::with_name("DECK").required(true))
+                .arg(
+                    Arg::with_name("assets")
+                        .long("assets")
+                        .takes_value(true)
+                        .default_value("."),
+                )
+                .arg(
+                    Arg::with_name("address")
+                        .long("address")
+                        .takes_value(true)
+                        .default_value("127.0.0.1:8000"),
+                )
+                .arg(
+                    Arg::with_name("max-connections")
+                        .long("max-connections")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("presenter-token")
+                        .long("presenter-token")
+                        .takes_value(true)
+                        .value_name("TOKEN")
+                        .help(
+                            "The secret needed to present, passed to the page \
+                             in its presenter-token query parameter, which is \
+                             generated if not given",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("share-poll-results")
+                        .long("share-poll-results")
+                        .help("Shows live poll results to the audience too"),
+                )
+                .arg(
+                    Arg::with_name("record-timeline")
+                        .long("record-timeline")
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .help(
+                            "Records the presenter's navigation into a \
+                             timeline file when the server stops",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("analytics")
+                        .long("analytics")
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .help(
+                            "Saves the time spent on each slide when the \
+                             server stops, as CSV if the file name ends with \
+                             .csv and as JSON otherwise",
+                        ),
+                ),
+        )
+        .get_matches();
+
+    let result = match matches.subcommand() {
+        ("build", Some(arguments)) => build(arguments),
+        ("lint", Some(arguments)) => lint(arguments),
+        ("serve", Some(arguments)) => serve(arguments).await,
+        _ => unreachable!("A subcommand is required"),
+    };
+
+    if let Err(error) = result {
+        eprintln!("Error: {}", error);
+
+        let mut source = error.source();
+
+        while let Some(cause) = source {
+            eprintln!("Caused by: {}", cause);
+            source = cause.source();
+        }
+
+        process::exit(1);
+    }
+}
+
+fn build(arguments: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let output = PathBuf::from(arguments.value_of("OUTPUT").unwrap());
+    let slides_output = output.join("slides");
+    let mut notes = load_notes(arguments.value_of("NOTES").unwrap())?;
+    let slides = Slides::from_notes(&notes)?;
+
+    fs::create_dir_all(&slides_output)?;
+    notes.generate_html(&output)?;
+    slides.write_to(&slides_output)?;
+
+    Ok(())
+}
+
+fn lint(arguments: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let mut options = LintOptions::default();
+
+    if let Some(max_steps) = arguments.value_of("max-steps") {
+        options.max_steps = max_steps.parse()?;
+    }
+
+    let mut report = LintReport::new();
+    let mut locales = Vec::new();
+
+    for path in arguments.values_of("NOTES").unwrap() {
+        let notes = load_notes(path)?;
+        let slides = Slides::from_notes(&notes)?;
+        let locale = Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_owned());
+
+        report.extend(notes.lint());
+        report.extend(slides.lint(&options)?);
+        locales.push((locale, slides));
+    }
+
+    if let Some(path) = arguments.value_of("config") {
+        let config: DeckConfig =
+            serde_json::from_str(&fs::read_to_string(path)?)?;
+
+        report.extend(config.lint(Some(Path::new(path))));
+    }
+
+    report.extend(Slides::lint_locales(
+        locales
+            .iter()
+            .map(|(locale, slides)| (locale.as_str(), slides)),
+    )?);
+
+    match arguments.value_of("format") {
+        Some("json") => println!("{}", report.to_json()?),
+        _ => print!("{}", report),
+    }
+
+    if report.has_errors() {
+        process::exit(1);
+    }
+
+    Ok(())
+}
+
+async fn serve(arguments: &ArgMatches<'_>) -> Result<(), Box<dyn Error>> {
+    let deck = Path::new(arguments.value_of("DECK").unwrap());
+    let address: SocketAddr = arguments.value_of("address").unwrap().parse()?;
+    let mut options = SyncOptions::default();
+
+    if let Some(max_connections) = arguments.value_of("max-connections") {
+        options.max_connections = max_connections.parse()?;
+    }
+
+    let presenter_token = match arguments.value_of("presenter-token") {
+        Some(presenter_token) => presenter_token.to_owned(),
+        None => generate_token()?,
+    };
+
+    options.presenter_token = Some(presenter_token.clone());
+    options.share_poll_results = arguments.is_present("share-poll-results");
+
+    let timeline_file =
+        arguments.value_of("record-timeline").map(PathBuf::from);
+    let analytics_file = arguments.value_of("analytics").map(PathBuf::from);
+
+    options.record_timeline =
+        timeline_file.is_some() || analytics_file.is_some();
+    options.time_targets = load_deck_config(deck, arguments)?.time_targets;
+
+    let presenter = SlidePresenter::with_options(options);
+    let router = if deck.is_dir() {
+        Presentrs::new_with_presenter(deck, presenter.clone())
+    } else {
+        Presentrs::from_markdown_with_presenter(
+            deck,
+            arguments.value_of("assets").unwrap(),
+            presenter.clone(),
+        )
+    };
+
+    println!("Serving presentation at http://{}", address);
+    println!(
+        "Present from http://{}/?presenter-token={}",
+        address, presenter_token,
+    );
+
+    let running_presenter = presenter.clone();
+
+    axum::Server::bind(&address)
+        .serve(router.into_make_service())
+        .with_graceful_shutdown(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                println!("Shutting down");
+                running_presenter.shutdown().await;
+            }
+        })
+        .await?;
+
+    if let (Some(path), Some(timeline)) = (timeline_file, presenter.timeline())
+    {
+        fs::write(&path, serde_json::to_string_pretty(&timeline)?)?;
+        println!("Saved timeline to {}", path.display());
+    }
+
+    if let (Some(path), Some(report)) =
+        (analytics_file, presenter.session_report())
+    {
+        let is_csv = path.extension() == Some("csv".as_ref());
+        let contents = if is_csv {
+            report.to_csv()
+        } else {
+            serde_json::to_string_pretty(&report)?
+        };
+
+        fs::write(&path, contents)?;
+        println!("Saved analytics to {}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Generates a presenter token from the operating system's random source.
+fn generate_token() -> Result<String, Box<dyn Error>> {
+    let mut bytes = [0; 16];
+
+    getrandom::getrandom(&mut bytes)?;
+
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Loads the `deck.json` served with the deck, which is in the deck's
+/// directory or among the assets of a markdown deck.
+fn load_deck_config(
+    deck: &Path,
+    arguments: &ArgMatches<'_>,
+) -> Result<DeckConfig, Box<dyn Error>> {
+    let directory = if deck.is_dir() {
+        deck
+    } else {
+        Path::new(arguments.value_of("assets").unwrap())
+    };
+    let path = directory.join(DeckConfig::FILE_NAME);
+
+    if path.exists() {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    } else {
+        Ok(DeckConfig::default())
+    }
+}
+
+fn load_notes(path: &str) -> Result<Notes, Box<dyn Error>> {
+    let mut notes = Notes::from_markdown(path)?;
+
+    notes.animate_steps()?;
+
+    Ok(notes)
+}
@@ -0,0 +1,26 @@
+use {
+    serde::{Deserialize, Serialize},
+    std::collections::BTreeMap,
+};
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Manifest {
+    pub slides: Vec<SlideSummary>,
+    /// How many options each poll of the deck has, by poll number.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub polls: BTreeMap<u32, u32>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct SlideSummary {
+    pub title: String,
+    pub text: String,
+}
+
+impl Manifest {
+    pub const FILE_NAME: &'static str = "manifest.json";
+
+    pub fn slide_count(&self) -> usize {
+        self.slides.len()
+    }
+}
@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+
+/// Moves through the slides and steps of a deck.
+///
+/// The step counts of slides are only known once they are loaded, so moving
+/// back into a slide whose step count is unknown places the navigator at
+/// `Navigator::UNKNOWN_LAST_STEP` until `set_step_count` is called for it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Navigator {
+    slide: usize,
+    step: usize,
+    step_counts: HashMap<usize, usize>,
+    slide_count: Option<usize>,
+}
+
+impl Default for Navigator {
+    fn default() -> Self {
+        Navigator::new()
+    }
+}
+
+impl Navigator {
+    pub const UNKNOWN_LAST_STEP: usize = usize::MAX;
+
+    pub fn new() -> Self {
+        Navigator::at(1, 1)
+    }
+
+    pub fn at(slide: usize, step: usize) -> Self {
+        Navigator {
+            slide: slide.max(1),
+            step: step.max(1),
+            step_counts: HashMap::new(),
+            slide_count: None,
+        }
+    }
+
+    pub fn slide(&self) -> usize {
+        self.slide
+    }
+
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    pub fn position(&self) -> (usize, usize) {
+        (self.slide, self.step)
+    }
+
+    pub fn is_waiting_for_step_count(&self) -> bool {
+        self.step == Self::UNKNOWN_LAST_STEP
+    }
+
+    pub fn slide_count(&self) -> Option<usize> {
+        self.slide_count
+    }
+
+    /// Sets the number of slides in the deck, with zero meaning unknown.
+    pub fn set_slide_count(&mut self, slide_count: usize) {
+        self.slide_count = Some(slide_count).filter(|&count| count > 0);
+    }
+
+    pub fn step_count(&self, slide: usize) -> Option<usize> {
+        self.step_counts.get(&slide).copied()
+    }
+
+    /// Records how many steps a slide has, completing a pending move to its
+    /// last step.
+    pub fn set_step_count(&mut self, slide: usize, step_count: usize) {
+        let step_count = step_count.max(1);
+
+        self.step_counts.insert(slide, step_count);
+
+        if self.slide == slide && self.is_waiting_for_step_count() {
+            self.step = step_count;
+        }
+    }
+
+    /// Moves to the first step of the first slide, returning whether the
+    /// position changed.
+    pub fn first_slide(&mut self) -> bool {
+        self.move_to(1, 1)
+    }
+
+    /// Moves to the first step of the last slide, returning whether the
+    /// position changed. Does nothing while the slide count is unknown.
+    pub fn last_slide(&mut self) -> bool {
+        match self.slide_count {
+            Some(slide_count) => self.move_to(slide_count, 1),
+            None => false,
+        }
+    }
+
+    /// Moves to the first step of a slide, kept within the deck's bounds.
+    pub fn jump_to_slide(&mut self, slide: usize) -> bool {
+        self.move_to(self.clamp_slide(slide), 1)
+    }
+
+    pub fn previous_slide(&mut self) -> bool {
+        self.move_to(self.slide.saturating_sub(1).max(1), 1)
+    }
+
+    pub fn next_slide(&mut self) -> bool {
+        if self.is_last_slide() {
+            self.move_to(self.slide, 1)
+        } else {
+            self.move_to(self.slide + 1, 1)
+        }
+    }
+
+    pub fn previous_step(&mut self) -> bool {
+        if self.is_waiting_for_step_count() {
+            false
+        } else if self.step > 1 {
+            self.move_to(self.slide, self.step - 1)
+        } else if self.slide > 1 {
+            let slide = self.slide - 1;
+            let step =
+                self.step_count(slide).unwrap_or(Self::UNKNOWN_LAST_STEP);
+
+            self.move_to(slide, step)
+        } else {
+            false
+        }
+    }
+
+    pub fn next_step(&mut self) -> bool {
+        let last_step = self
+            .step_count(self.slide)
+            .unwrap_or(Self::UNKNOWN_LAST_STEP);
+
+        if self.step < last_step {
+            self.move_to(self.slide, self.step + 1)
+        } else if self.is_last_slide() {
+            false
+        } else {
+            self.move_to(self.slide + 1, 1)
+        }
+    }
+
+    /// Moves to a position set elsewhere, such as a synchronization server
+    /// or the URL.
+    pub fn change_position(&mut self, slide: usize, step: usize) -> bool {
+        self.move_to(slide.max(1), step.max(1))
+    }
+
+    fn is_last_slide(&self) -> bool {
+        matches!(self.slide_count, Some(count) if self.slide >= count)
+    }
+
+    fn clamp_slide(&self, slide: usize) -> usize {
+        match self.slide_count {
+            Some(slide_count) => slide.min(slide_count).max(1),
+            None => slide.max(1),
+        }
+    }
+
+    fn move_to(&mut self, slide: usize, step: usize) -> bool {
+        let changed = (slide, step) != self.position();
+
+        self.slide = slide;
+        self.step = step;
+
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Navigator;
+
+    #[test]
+    fn starts_at_the_first_step_of_the_first_slide() {
+        let navigator = Navigator::new();
+
+        assert_eq!(navigator.position(), (1, 1));
+        assert_eq!(navigator.slide_count(), None);
+    }
+
+    #[test]
+    fn positions_are_numbered_from_one() {
+        assert_eq!(Navigator::at(0, 0).position(), (1, 1));
+
+        let mut navigator = Navigator::new();
+
+        assert!(!navigator.change_position(0, 0));
+        assert_eq!(navigator.position(), (1, 1));
+    }
+
+    #[test]
+    fn next_step_advances_within_a_slide_with_known_steps() {
+        let mut navigator = Navigator::new();
+
+        navigator.set_step_count(1, 3);
+
+        assert!(navigator.next_step());
+        assert_eq!(navigator.position(), (1, 2));
+        assert!(navigator.next_step());
+        assert_eq!(navigator.position(), (1, 3));
+        assert!(navigator.next_step());
+        assert_eq!(navigator.position(), (2, 1));
+    }
+
+    #[test]
+    fn next_step_keeps_advancing_while_the_step_count_is_unknown() {
+        let mut navigator = Navigator::new();
+
+        assert!(navigator.next_step());
+        assert!(navigator.next_step());
+        assert_eq!(navigator.position(), (1, 3));
+    }
+
+    #[test]
+    fn next_step_stops_at_the_last_step_of_the_deck() {
+        let mut navigator = Navigator::at(2, 2);
+
+        navigator.set_slide_count(2);
+        navigator.set_step_count(2, 2);
+
+        assert!(!navigator.next_step());
+        assert_eq!(navigator.position(), (2, 2));
+    }
+
+    #[test]
+    fn previous_step_moves_to_the_last_step_of_the_previous_slide() {
+        let mut navigator = Navigator::at(2, 1);
+
+        navigator.set_step_count(1, 4);
+
+        assert!(navigator.previous_step());
+        assert_eq!(navigator.position(), (1, 4));
+        assert!(navigator.previous_step());
+        assert_eq!(navigator.position(), (1, 3));
+    }
+
+    #[test]
+    fn previous_step_waits_for_the_step_count_of_an_unloaded_slide() {
+        let mut navigator = Navigator::at(2, 1);
+
+        assert!(navigator.previous_step());
+        assert_eq!(navigator.slide(), 1);
+        assert!(navigator.is_waiting_for_step_count());
+        assert_eq!(navigator.step(), Navigator::UNKNOWN_LAST_STEP);
+
+        assert!(!navigator.previous_step());
+
+        navigator.set_step_count(3, 2);
+        assert!(navigator.is_waiting_for_step_count());
+
+        navigator.set_step_count(1, 5);
+        assert_eq!(navigator.position(), (1, 5));
+    }
+
+    #[test]
+    fn previous_step_does_nothing_at_the_start_of_the_deck() {
+        let mut navigator = Navigator::new();
+
+        assert!(!navigator.previous_step());
+        assert_eq!(navigator.position(), (1, 1));
+    }
+
+    #[test]
+    fn slides_without_steps_count_as_having_one_step() {
+        let mut navigator = Navigator::new();
+
+        navigator.set_step_count(1, 0);
+
+        assert_eq!(navigator.step_count(1), Some(1));
+        assert!(navigator.next_step());
+        assert_eq!(navigator.position(), (2, 1));
+    }
+
+    #[test]
+    fn slide_moves_reset_the_step() {
+        let mut navigator = Navigator::at(2, 3);
+
+        assert!(navigator.next_slide());
+        assert_eq!(navigator.position(), (3, 1));
+        assert!(navigator.previous_slide());
+        assert_eq!(navigator.position(), (2, 1));
+        assert!(navigator.first_slide());
+        assert_eq!(navigator.position(), (1, 1));
+        assert!(!navigator.previous_slide());
+    }
+
+    #[test]
+    fn next_slide_stops_at_the_last_slide() {
+        let mut navigator = Navigator::at(3, 2);
+
+        navigator.set_slide_count(3);
+
+        assert!(navigator.next_slide());
+        assert_eq!(navigator.position(), (3, 1));
+        assert!(!navigator.next_slide());
+    }
+
+    #[test]
+    fn last_slide_requires_a_known_slide_count() {
+        let mut navigator = Navigator::new();
+
+        assert!(!navigator.last_slide());
+
+        navigator.set_slide_count(0);
+        assert!(!navigator.last_slide());
+
+        navigator.set_slide_count(7);
+        assert!(navigator.last_slide());
+        assert_eq!(navigator.position(), (7, 1));
+    }
+
+    #[test]
+    fn jump_to_slide_is_kept_within_bounds() {
+        let mut navigator = Navigator::new();
+
+        assert!(navigator.jump_to_slide(42));
+        assert_eq!(navigator.position(), (42, 1));
+
+        navigator.set_slide_count(10);
+
+        assert!(navigator.jump_to_slide(42));
+        assert_eq!(navigator.position(), (10, 1));
+        assert!(navigator.jump_to_slide(0));
+        assert_eq!(navigator.position(), (1, 1));
+    }
+
+    #[test]
+    fn change_position_reports_whether_it_moved() {
+        let mut navigator = Navigator::new();
+
+        assert!(navigator.change_position(4, 2));
+        assert_eq!(navigator.position(), (4, 2));
+        assert!(!navigator.change_position(4, 2));
+    }
+}
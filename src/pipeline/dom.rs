@@ -0,0 +1,17 @@
+use markup5ever_arcdom::{Handle, NodeData};
+
+pub(crate) fn find_element(
+    node: &Handle,
+    element_name: &str,
+) -> Option<Handle> {
+    if let NodeData::Element { ref name, .. } = node.data {
+        if &name.local == element_name {
+            return Some(node.clone());
+        }
+    }
+
+    node.children
+        .borrow()
+        .iter()
+        .find_map(|child| find_element(child, element_name))
+}
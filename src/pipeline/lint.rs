@@ -0,0 +1,479 @@
+use {
+    crate::{config::DeckConfig, key_bindings::KeyBindings},
+    serde::Serialize,
+    std::{
+        fmt::{self, Display, Formatter},
+        path::{Path, PathBuf},
+    },
+};
+
+#[derive(Clone, Copy, Debug)]
+pub struct LintOptions {
+    pub max_steps: usize,
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        // The generated `notes.css` only styles the first 20 steps.
+        LintOptions { max_steps: 20 }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct LintReport {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl LintReport {
+    pub fn new() -> Self {
+        LintReport::default()
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    pub fn extend(&mut self, other: LintReport) {
+        self.diagnostics.extend(other.diagnostics);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error)
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+impl Display for LintReport {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        for diagnostic in &self.diagnostics {
+            writeln!(formatter, "{}", diagnostic)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub kind: DiagnosticKind,
+    pub location: Location,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn error(
+        kind: DiagnosticKind,
+        location: Location,
+        message: impl Into<String>,
+    ) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            kind,
+            location,
+            message: message.into(),
+        }
+    }
+
+    pub fn warning(
+        kind: DiagnosticKind,
+        location: Location,
+        message: impl Into<String>,
+    ) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            kind,
+            location,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{}: {}: {}",
+            self.location, self.severity, self.message,
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(formatter, "error"),
+            Severity::Warning => write!(formatter, "warning"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiagnosticKind {
+    InvalidStepSpec,
+    StepGap,
+    EmptySlide,
+    TooManySteps,
+    BrokenLink,
+    BrokenImage,
+    LocaleMismatch,
+    InvalidKeyBinding,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Location {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slide: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+}
+
+impl Display for Location {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        if let Some(ref locale) = self.locale {
+            write!(formatter, "[{}] ", locale)?;
+        }
+
+        match (&self.file, self.slide) {
+            (Some(file), slide) => {
+                write!(formatter, "{}", file.display())?;
+
+                if let Some(line) = self.line {
+                    write!(formatter, ":{}", line)?;
+                }
+
+                if let Some(slide) = slide {
+                    write!(formatter, " (slide {})", slide)?;
+                }
+
+                Ok(())
+            }
+            (None, Some(slide)) => write!(formatter, "slide {}", slide),
+            (None, None) => write!(formatter, "deck"),
+        }
+    }
+}
+
+impl DeckConfig {
+    /// Checks the configuration loaded from a `deck.json` file.
+    pub fn lint(&self, file: Option<&Path>) -> LintReport {
+        let mut report = LintReport::new();
+        let location = Location {
+            file: file.map(Path::to_owned),
+            ..Location::default()
+        };
+
+        for error in KeyBindings::empty().apply_config(&self.key_bindings) {
+            report.push(Diagnostic::error(
+                DiagnosticKind::InvalidKeyBinding,
+                location.clone(),
+                error,
+            ));
+        }
+
+        report
+    }
+}
+
+/// Where each slide generated from a markdown file came from.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SourceMap {
+    pub(crate) file: Option<PathBuf>,
+    pub(crate) slides: Vec<Option<SlideSource>>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SlideSource {
+    pub(crate) line: usize,
+    pub(crate) step_spec_lines: Vec<usize>,
+}
+
+impl SourceMap {
+    /// Drops the location of a slide that was replaced by another source.
+    pub(crate) fn forget(&mut self, slide: usize) {
+        if let Some(source) = slide
+            .checked_sub(1)
+            .and_then(|index| self.slides.get_mut(index))
+        {
+            *source = None;
+        }
+    }
+
+    pub(crate) fn locate(
+        &self,
+        slide: usize,
+        step_spec: Option<usize>,
+    ) -> Location {
+        let slide_source = slide
+            .checked_sub(1)
+            .and_then(|index| self.slides.get(index))
+            .and_then(Option::as_ref);
+
+        match slide_source {
+            Some(source) => Location {
+                file: self.file.clone(),
+                line: Some(
+                    step_spec
+                        .and_then(|index| source.step_spec_lines.get(index))
+                        .copied()
+                        .unwrap_or(source.line),
+                ),
+                slide: Some(slide),
+                locale: None,
+            },
+            None => Location {
+                slide: Some(slide),
+                ..Location::default()
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{
+            DiagnosticKind, LintOptions, Location, Severity, SlideSource,
+            SourceMap,
+        },
+        crate::{
+            config::DeckConfig,
+            pipeline::{Notes, Slides},
+        },
+        std::{env, fs, path::PathBuf, process},
+    };
+
+    struct SlidesDirectory(PathBuf);
+
+    impl SlidesDirectory {
+        fn new(name: &str, slides: &[&str]) -> Self {
+            let path = env::temp_dir().join(format!(
+                "presentrs-lint-{}-{}",
+                process::id(),
+                name,
+            ));
+
+            fs::create_dir_all(&path).unwrap();
+
+            for (slide_number, slide) in (1..).zip(slides) {
+                fs::write(path.join(format!("{}.html", slide_number)), slide)
+                    .unwrap();
+            }
+
+            SlidesDirectory(path)
+        }
+
+        fn load(&self) -> Slides {
+            let mut slides =
+                Slides::from_notes(&Notes::from_markdown_str("")).unwrap();
+
+            slides.load_from(&self.0).unwrap();
+            slides
+        }
+    }
+
+    impl Drop for SlidesDirectory {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn kinds(slides: &Slides) -> Vec<(DiagnosticKind, Option<usize>)> {
+        slides
+            .lint(&LintOptions::default())
+            .unwrap()
+            .diagnostics
+            .into_iter()
+            .map(|diagnostic| (diagnostic.kind, diagnostic.location.slide))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_well_formed_slides() {
+        let directory = SlidesDirectory::new(
+            "well-formed",
+            &[
+                r#"<div><p data-slide-steps="1">A</p><p data-slide-steps="+-">B</p></div>"#,
+            ],
+        );
+
+        assert!(directory
+            .load()
+            .lint(&LintOptions::default())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn reports_problems_in_each_slide() {
+        let directory = SlidesDirectory::new(
+            "problems",
+            &[
+                r#"<div><p data-slide-steps="1">A</p><p data-slide-steps="3">B</p></div>"#,
+                "<div> </div>",
+                r#"<div><p data-slide-steps="2-1">A</p></div>"#,
+                r#"<div><p data-slide-steps="1-21">A</p></div>"#,
+            ],
+        );
+        let slides = directory.load();
+
+        assert_eq!(
+            kinds(&slides),
+            vec![
+                (DiagnosticKind::StepGap, Some(1)),
+                (DiagnosticKind::EmptySlide, Some(2)),
+                (DiagnosticKind::InvalidStepSpec, Some(3)),
+                (DiagnosticKind::TooManySteps, Some(4)),
+            ],
+        );
+
+        let report = slides.lint(&LintOptions { max_steps: 30 }).unwrap();
+
+        assert_eq!(report.diagnostics.len(), 3);
+        assert!(report.has_errors());
+        assert_eq!(
+            report.diagnostics[0].message,
+            "No element is shown in step(s) 2",
+        );
+    }
+
+    #[test]
+    fn reports_locales_with_different_slides_or_steps() {
+        let reference = SlidesDirectory::new(
+            "locale-reference",
+            &[
+                r#"<div><p data-slide-steps="1-2">A</p></div>"#,
+                "<div>B</div>",
+            ],
+        );
+        let translation = SlidesDirectory::new(
+            "locale-translation",
+            &[r#"<div><p data-slide-steps="1">A</p></div>"#],
+        );
+        let (reference, translation) = (reference.load(), translation.load());
+
+        let report = Slides::lint_locales(vec![
+            ("en", &reference),
+            ("pt", &translation),
+        ])
+        .unwrap();
+        let locations: Vec<_> = report
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                assert_eq!(diagnostic.kind, DiagnosticKind::LocaleMismatch);
+                diagnostic.location.to_string()
+            })
+            .collect();
+
+        assert_eq!(locations, vec!["[pt] deck", "[pt] slide 1"]);
+    }
+
+    #[test]
+    fn reports_broken_links_at_their_line() {
+        let notes = Notes::from_markdown_str("# Slide\n\nSee [this]().\n");
+        let report = notes.lint();
+
+        assert_eq!(report.diagnostics.len(), 1);
+
+        let diagnostic = &report.diagnostics[0];
+
+        assert_eq!(diagnostic.kind, DiagnosticKind::BrokenLink);
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.location.line, Some(3));
+    }
+
+    #[test]
+    fn reports_invalid_key_bindings() {
+        let mut config = DeckConfig::default();
+
+        config
+            .key_bindings
+            .insert("n".to_owned(), "next-step".to_owned());
+        config
+            .key_bindings
+            .insert("x".to_owned(), "explode".to_owned());
+
+        let report = config.lint(Some("deck.json".as_ref()));
+
+        assert_eq!(report.diagnostics.len(), 1);
+        assert_eq!(
+            report.diagnostics[0].kind,
+            DiagnosticKind::InvalidKeyBinding,
+        );
+        assert!(report.diagnostics[0]
+            .to_string()
+            .starts_with("deck.json: error: "));
+    }
+
+    #[test]
+    fn locates_slides_and_step_specs_in_the_source() {
+        let mut source_map = SourceMap {
+            file: Some("notes.md".into()),
+            slides: vec![
+                Some(SlideSource {
+                    line: 1,
+                    step_spec_lines: vec![4],
+                }),
+                Some(SlideSource {
+                    line: 7,
+                    step_spec_lines: vec![],
+                }),
+            ],
+        };
+
+        assert_eq!(
+            source_map.locate(1, Some(0)).to_string(),
+            "notes.md:4 (slide 1)"
+        );
+        assert_eq!(
+            source_map.locate(1, Some(1)).to_string(),
+            "notes.md:1 (slide 1)"
+        );
+        assert_eq!(
+            source_map.locate(2, None).to_string(),
+            "notes.md:7 (slide 2)"
+        );
+        assert_eq!(source_map.locate(3, None).to_string(), "slide 3");
+
+        source_map.forget(2);
+
+        assert_eq!(source_map.locate(2, None).to_string(), "slide 2");
+    }
+
+    #[test]
+    fn serializes_reports_as_json() {
+        let config: DeckConfig =
+            serde_json::from_str(r#"{ "key_bindings": { "x": "explode" } }"#)
+                .unwrap();
+        let json = config.lint(None).to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let diagnostic = &value["diagnostics"][0];
+
+        assert_eq!(diagnostic["severity"], "error");
+        assert_eq!(diagnostic["kind"], "invalid-key-binding");
+        assert_eq!(diagnostic["location"], serde_json::json!({}));
+        assert_eq!(Location::default().to_string(), "deck");
+    }
+}
@@ -0,0 +1,13 @@
+mod dom;
+mod lint;
+mod notes;
+mod poll;
+mod slides;
+
+pub use self::{
+    lint::{
+        Diagnostic, DiagnosticKind, LintOptions, LintReport, Location, Severity,
+    },
+    notes::{Notes, NotesError},
+    slides::{Iter as SlidesIter, Slides, SlidesError},
+};
@@ -0,0 +1,369 @@
+use {
+    super::{
+        dom::find_element,
+        lint::{
+            Diagnostic, DiagnosticKind, LintReport, Location, SlideSource,
+            SourceMap,
+        },
+        poll::Poll,
+    },
+    crate::step_spec::STEPS_ATTRIBUTE,
+    comrak::{
+        arena_tree::Node,
+        markdown_to_html,
+        nodes::{Ast, NodeValue},
+        parse_document as parse_markdown, Arena, ComrakOptions,
+    },
+    derive_more::{Display, Error},
+    html5ever::{
+        driver::ParseOpts,
+        interface::{Attribute, QualName},
+        parse_document, serialize,
+        serialize::SerializeOpts,
+        tendril::TendrilSink,
+    },
+    markup5ever_arcdom::{ArcDom, Handle, NodeData, SerializableHandle},
+    std::{
+        borrow::BorrowMut,
+        cell::RefCell,
+        io::Cursor,
+        path::{Path, PathBuf},
+        {fs, io},
+    },
+};
+
+type MarkdownNode<'a> = Node<'a, RefCell<Ast>>;
+
+#[derive(Debug)]
+pub struct Notes {
+    output: String,
+    style: Option<String>,
+    markdown: String,
+    path: Option<PathBuf>,
+}
+
+impl Notes {
+    pub fn from_markdown<P: AsRef<Path>>(
+        markdown_file: P,
+    ) -> Result<Self, NotesError> {
+        let markdown_file = markdown_file.as_ref();
+        let markdown =
+            fs::read_to_string(markdown_file).map_err(NotesError::LoadError)?;
+        let mut notes = Self::from_markdown_str(markdown);
+
+        notes.path = Some(markdown_file.to_owned());
+
+        Ok(notes)
+    }
+
+    pub fn from_markdown_str(markdown: impl Into<String>) -> Self {
+        let markdown = markdown.into();
+        let html = markdown_to_html(&markdown, &ComrakOptions::default());
+        let output = format!("<div>{}</div>", html);
+
+        Notes {
+            output,
+            style: None,
+            markdown,
+            path: None,
+        }
+    }
+
+    /// Checks the links and images in the markdown source.
+    ///
+    /// Checks on the generated slides are performed by `Slides::lint`.
+    pub fn lint(&self) -> LintReport {
+        let arena = Arena::new();
+        let root =
+            parse_markdown(&arena, &self.markdown, &ComrakOptions::default());
+        let mut report = LintReport::new();
+
+        for node in root.descendants() {
+            let (kind, url) = match node.data.borrow().value {
+                NodeValue::Link(ref link) => {
+                    (DiagnosticKind::BrokenLink, link.url.clone())
+                }
+                NodeValue::Image(ref image) => {
+                    (DiagnosticKind::BrokenImage, image.url.clone())
+                }
+                _ => continue,
+            };
+            let url = String::from_utf8_lossy(&url);
+
+            if let Some(problem) = self.check_url(&url) {
+                let location = Location {
+                    file: self.path.clone(),
+                    line: Some(Self::line_of(node)),
+                    ..Location::default()
+                };
+
+                report.push(Diagnostic::error(kind, location, problem));
+            }
+        }
+
+        report
+    }
+
+    fn check_url(&self, url: &str) -> Option<String> {
+        let url = url.trim();
+
+        if url.is_empty() {
+            return Some("Empty URL".to_owned());
+        }
+
+        if url.starts_with('#') || url.starts_with('/') || Self::has_scheme(url)
+        {
+            return None;
+        }
+
+        let path = url.split(['?', '#']).next().unwrap_or_default();
+        let base = self
+            .path
+            .as_ref()
+            .and_then(|path| path.parent())
+            .unwrap_or_else(|| Path::new(""));
+
+        if path.is_empty() || base.join(path).exists() {
+            None
+        } else {
+            Some(format!("File not found: {}", path))
+        }
+    }
+
+    fn has_scheme(url: &str) -> bool {
+        match url.find(':') {
+            Some(end) => url[..end].chars().all(|character| {
+                character.is_ascii_alphanumeric()
+                    || character == '+'
+                    || character == '-'
+                    || character == '.'
+            }),
+            None => false,
+        }
+    }
+
+    fn line_of<'a>(node: &'a MarkdownNode<'a>) -> usize {
+        node.ancestors()
+            .map(|ancestor| ancestor.data.borrow().start_line)
+            .find(|&line| line > 0)
+            .unwrap_or(1) as usize
+    }
+
+    pub(crate) fn source_map(&self) -> SourceMap {
+        let arena = Arena::new();
+        let root =
+            parse_markdown(&arena, &self.markdown, &ComrakOptions::default());
+        let mut slides: Vec<Option<SlideSource>> = Vec::new();
+
+        for node in root.descendants() {
+            let ast = node.data.borrow();
+
+            match ast.value {
+                NodeValue::Heading(ref heading) if heading.level <= 3 => {
+                    slides.push(Some(SlideSource {
+                        line: ast.start_line as usize,
+                        step_spec_lines: Vec::new(),
+                    }));
+                }
+                NodeValue::HtmlBlock(ref block) => {
+                    if let Some(Some(slide)) = slides.last_mut() {
+                        let block = String::from_utf8_lossy(&block.literal);
+
+                        for (offset, line) in block.lines().enumerate() {
+                            for _ in line.matches(STEPS_ATTRIBUTE) {
+                                slide
+                                    .step_spec_lines
+                                    .push(ast.start_line as usize + offset);
+                            }
+                        }
+                    }
+                }
+                NodeValue::HtmlInline(ref html) => {
+                    if let Some(Some(slide)) = slides.last_mut() {
+                        let html = String::from_utf8_lossy(html);
+                        let line = Self::line_of(node);
+
+                        for _ in html.matches(STEPS_ATTRIBUTE) {
+                            slide.step_spec_lines.push(line);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        SourceMap {
+            file: self.path.clone(),
+            slides,
+        }
+    }
+
+    /// Splits the notes into slides and steps, and turns poll code blocks
+    /// into interactive polls.
+    pub fn animate_steps(&mut self) -> Result<&mut Self, NotesError> {
+        let html_dom = parse_document(ArcDom::default(), ParseOpts::default())
+            .from_utf8()
+            .read_from(&mut self.output.as_bytes())
+            .map_err(NotesError::AnimateStepsError)?;
+        let mut body = find_element(&html_dom.document, "body")
+            .ok_or(NotesError::MissingBody)?;
+
+        let mut slide = 0;
+        let mut step = 0;
+        let mut polls = 0;
+
+        Poll::embed_all(&body, &mut polls);
+        Self::animate_steps_on(&mut slide, &mut step, &mut body);
+
+        let mut style = String::new();
+
+        for i in 1..=slide {
+            for j in 1..=slide {
+                if i != j {
+                    style.push_str(&format!(
+                        "div.current-slide-{} .slide-{} {{ display: none; }}",
+                        i, j
+                    ));
+                }
+            }
+        }
+
+        for i in 1..=20 {
+            for j in 1..=20 {
+                style.push_str(&format!(
+                    "div.current-slide-step-{} .slide-step-{} ",
+                    i, j
+                ));
+
+                if i == j {
+                    style.push_str("{ color: black; visibility: visible; }");
+                } else if i > j {
+                    style.push_str("{ color: lightgray; }");
+                } else if i < j {
+                    style.push_str("{ visibility: hidden; }");
+                }
+            }
+        }
+
+        let mut output = Cursor::new(Vec::new());
+
+        serialize(
+            &mut output,
+            &SerializableHandle::from(body.clone()),
+            SerializeOpts::default(),
+        )
+        .map_err(NotesError::AnimateStepsError)?;
+
+        self.output = String::from_utf8_lossy(output.get_ref()).to_string();
+        self.style = Some(style);
+
+        Ok(self)
+    }
+
+    fn animate_steps_on(
+        slide: &mut usize,
+        step: &mut usize,
+        node: &mut Handle,
+    ) {
+        let node = node.borrow_mut();
+
+        if let NodeData::Element {
+            ref name,
+            ref attrs,
+            ..
+        } = node.data
+        {
+            if &name.local == "h1" || &name.local == "h2" || &name.local == "h3"
+            {
+                *slide += 1;
+                *step = 0;
+            } else if &name.local == "li" {
+                *step += 1;
+            }
+
+            if *slide > 0 {
+                let step_classes = if *step > 0 {
+                    format!("slide-{} slide-step-{}", slide, step)
+                } else {
+                    format!("slide-{}", slide)
+                };
+
+                let mut attributes = attrs.borrow_mut();
+                let new_attribute = {
+                    let attribute = attributes
+                        .iter_mut()
+                        .find(|attribute| &attribute.name.local == "class");
+
+                    match attribute {
+                        Some(class_attribute) => {
+                            let new_value = format!(
+                                "{} {}",
+                                class_attribute.value, step_classes,
+                            );
+
+                            class_attribute.value = new_value.into();
+
+                            None
+                        }
+                        None => {
+                            let namespace = "".into();
+                            let name =
+                                QualName::new(None, namespace, "class".into());
+                            let value = step_classes.into();
+
+                            Some(Attribute { name, value })
+                        }
+                    }
+                };
+
+                if let Some(attribute) = new_attribute {
+                    attributes.push(attribute);
+                }
+            }
+        }
+
+        for child in node.children.borrow_mut().iter_mut() {
+            Self::animate_steps_on(slide, step, child);
+        }
+    }
+
+    pub fn generate_html<P: AsRef<Path>>(
+        &mut self,
+        output_dir: P,
+    ) -> Result<(), NotesError> {
+        let output_dir = output_dir.as_ref();
+        let notes_html = output_dir.join("notes.html");
+
+        fs::write(notes_html, &self.output)
+            .map_err(NotesError::GenerateHtmlError)?;
+
+        if let Some(ref style) = self.style {
+            let notes_css = output_dir.join("notes.css");
+
+            fs::write(notes_css, style).map_err(NotesError::GenerateHtmlError)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn html(&self) -> &str {
+        &self.output
+    }
+
+    /// The style sheet generated by `animate_steps`, if it was called.
+    pub fn css(&self) -> Option<&str> {
+        self.style.as_deref()
+    }
+}
+
+#[derive(Debug, Display, Error)]
+pub enum NotesError {
+    #[display(fmt = "Failed to load notes")]
+    LoadError(io::Error),
+    #[display(fmt = "Failed to animate steps for notes")]
+    AnimateStepsError(io::Error),
+    #[display(fmt = "Notes have no body element to animate")]
+    MissingBody,
+    #[display(fmt = "Failed to generate HTML file for notes")]
+    GenerateHtmlError(io::Error),
+}
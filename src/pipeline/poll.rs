@@ -0,0 +1,238 @@
+use {
+    html5ever::{
+        interface::{Attribute, QualName},
+        local_name, namespace_url, ns,
+        tendril::StrTendril,
+        LocalName,
+    },
+    markup5ever_arcdom::{Handle, Node, NodeData},
+    std::{cell::RefCell, collections::BTreeMap, sync::Arc},
+};
+
+/// The language of the fenced code blocks that are turned into polls.
+const POLL_LANGUAGE: &str = "language-poll";
+
+/// The attribute with the number of a poll element.
+const POLL_ATTRIBUTE: &str = "data-poll";
+
+/// The attribute with the index of a poll option's button.
+const OPTION_ATTRIBUTE: &str = "data-poll-option";
+
+/// A question for the audience, written in the markdown as a fenced code
+/// block with the `poll` language:
+///
+/// ````markdown
+/// ```poll
+/// Which editor do you use?
+/// - Vim
+/// - Emacs
+/// - Something else
+/// ```
+/// ````
+///
+/// The first line that isn't an option is the question, and every line
+/// starting with `-` or `*` is an option.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Poll {
+    question: String,
+    options: Vec<String>,
+}
+
+impl Poll {
+    pub fn parse(source: &str) -> Option<Self> {
+        let mut question = None;
+        let mut options = Vec::new();
+
+        for line in source.lines().map(str::trim) {
+            if let Some(option) =
+                line.strip_prefix('-').or_else(|| line.strip_prefix('*'))
+            {
+                options.push(option.trim().to_owned());
+            } else if !line.is_empty() && question.is_none() {
+                question = Some(line.to_owned());
+            }
+        }
+
+        if options.is_empty() {
+            None
+        } else {
+            Some(Poll {
+                question: question.unwrap_or_default(),
+                options,
+            })
+        }
+    }
+
+    /// Replaces the poll code blocks under a node with interactive poll
+    /// elements, numbering them in document order from `next_id`.
+    pub fn embed_all(node: &Handle, next_id: &mut u32) {
+        let mut children = node.children.borrow_mut();
+
+        for child in children.iter_mut() {
+            match Self::from_code_block(child) {
+                Some(poll) => {
+                    *next_id += 1;
+
+                    let element = poll.to_element(*next_id);
+
+                    element.parent.set(child.parent.take());
+                    *child = element;
+                }
+                None => Self::embed_all(child, next_id),
+            }
+        }
+    }
+
+    /// Counts the options of the poll elements under a node, by poll number.
+    pub fn count_options(node: &Handle, polls: &mut BTreeMap<u32, u32>) {
+        let poll = Self::attribute(node, POLL_ATTRIBUTE)
+            .and_then(|poll| poll.parse().ok());
+
+        match poll {
+            Some(poll) => {
+                polls.insert(poll, Self::option_count(node));
+            }
+            None => {
+                for child in node.children.borrow().iter() {
+                    Self::count_options(child, polls);
+                }
+            }
+        }
+    }
+
+    fn option_count(node: &Handle) -> u32 {
+        let own_count =
+            Self::attribute(node, OPTION_ATTRIBUTE).map_or(0, |_| 1);
+
+        node.children
+            .borrow()
+            .iter()
+            .map(Self::option_count)
+            .fold(own_count, u32::saturating_add)
+    }
+
+    fn attribute(node: &Handle, attribute_name: &str) -> Option<String> {
+        match node.data {
+            NodeData::Element { ref attrs, .. } => attrs
+                .borrow()
+                .iter()
+                .find(|attribute| &attribute.name.local == attribute_name)
+                .map(|attribute| attribute.value.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Parses the `<pre><code class="language-poll">` elements generated
+    /// for poll code blocks.
+    fn from_code_block(node: &Handle) -> Option<Self> {
+        if !Self::is_element(node, "pre") {
+            return None;
+        }
+
+        let children = node.children.borrow();
+        let code = match children.as_slice() {
+            [code] if Self::is_element(code, "code") => code,
+            _ => return None,
+        };
+
+        if let NodeData::Element { ref attrs, .. } = code.data {
+            let is_poll = attrs.borrow().iter().any(|attribute| {
+                &attribute.name.local == "class"
+                    && attribute
+                        .value
+                        .split_whitespace()
+                        .any(|class| class == POLL_LANGUAGE)
+            });
+
+            if !is_poll {
+                return None;
+            }
+        }
+
+        let mut source = String::new();
+
+        for text in code.children.borrow().iter() {
+            if let NodeData::Text { ref contents } = text.data {
+                source.push_str(&contents.borrow());
+            }
+        }
+
+        Self::parse(&source)
+    }
+
+    /// Builds the poll's markup, which the client makes interactive:
+    ///
+    /// ```html
+    /// <div class="poll" data-poll="1">
+    ///   <p class="poll-question">Which editor do you use?</p>
+    ///   <button class="poll-option" data-poll-option="0">
+    ///     Vim <span class="poll-votes"></span>
+    ///   </button>
+    ///   ...
+    /// </div>
+    /// ```
+    fn to_element(&self, id: u32) -> Handle {
+        let poll = Self::element(
+            local_name!("div"),
+            &[("class", "poll"), (POLL_ATTRIBUTE, &id.to_string())],
+        );
+        let question =
+            Self::element(local_name!("p"), &[("class", "poll-question")]);
+
+        Self::append(&question, Self::text(&self.question));
+        Self::append(&poll, question);
+
+        for (index, option) in self.options.iter().enumerate() {
+            let button = Self::element(
+                local_name!("button"),
+                &[
+                    ("class", "poll-option"),
+                    (OPTION_ATTRIBUTE, &index.to_string()),
+                ],
+            );
+            let votes =
+                Self::element(local_name!("span"), &[("class", "poll-votes")]);
+
+            Self::append(&button, Self::text(&format!("{} ", option)));
+            Self::append(&button, votes);
+            Self::append(&poll, button);
+        }
+
+        poll
+    }
+
+    fn is_element(node: &Handle, element_name: &str) -> bool {
+        matches!(
+            node.data,
+            NodeData::Element { ref name, .. } if &name.local == element_name
+        )
+    }
+
+    fn element(name: LocalName, attributes: &[(&str, &str)]) -> Handle {
+        let attrs = attributes
+            .iter()
+            .map(|&(name, value)| Attribute {
+                name: QualName::new(None, "".into(), name.into()),
+                value: value.into(),
+            })
+            .collect();
+
+        Node::new(NodeData::Element {
+            name: QualName::new(None, ns!(html), name),
+            attrs: RefCell::new(attrs),
+            template_contents: None,
+            mathml_annotation_xml_integration_point: false,
+        })
+    }
+
+    fn text(contents: &str) -> Handle {
+        Node::new(NodeData::Text {
+            contents: RefCell::new(StrTendril::from_slice(contents)),
+        })
+    }
+
+    fn append(parent: &Handle, child: Handle) {
+        child.parent.set(Some(Arc::downgrade(parent)));
+        parent.children.borrow_mut().push(child);
+    }
+}
@@ -0,0 +1,785 @@
+use {
+    super::{
+        dom::find_element,
+        lint::{
+            Diagnostic, DiagnosticKind, LintOptions, LintReport, Location,
+            SourceMap,
+        },
+        notes::Notes,
+        poll::Poll,
+    },
+    crate::{
+        manifest::{Manifest, SlideSummary},
+        step_spec::{
+            StepContext, StepRange, StepSpec, StepSpecError, LABEL_ATTRIBUTE,
+            STEPS_ATTRIBUTE,
+        },
+    },
+    derive_more::{Display, Error},
+    html5ever::{
+        driver::ParseOpts,
+        interface::Attribute,
+        parse_document, serialize,
+        serialize::{SerializeOpts, TraversalScope},
+        tendril::TendrilSink,
+    },
+    markup5ever_arcdom::{ArcDom, Handle, Node, NodeData, SerializableHandle},
+    std::{
+        cell::RefCell,
+        collections::{BTreeMap, HashMap},
+        ffi::OsStr,
+        fs::{self, DirEntry},
+        io, iter,
+        path::{Path, PathBuf},
+        slice,
+    },
+};
+
+pub type Iter<'a> = iter::Map<slice::Iter<'a, String>, fn(&String) -> &str>;
+
+pub struct Slides {
+    slides: Vec<String>,
+    source: Option<SourceMap>,
+}
+
+impl<'a> IntoIterator for &'a Slides {
+    type Item = &'a str;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+type StepSpecResult = Result<Vec<StepRange>, StepSpecError>;
+
+impl Slides {
+    pub fn from_notes(notes: &Notes) -> Result<Slides, SlidesError> {
+        let html = notes.html();
+        let html_dom = parse_document(ArcDom::default(), ParseOpts::default())
+            .from_utf8()
+            .read_from(&mut html.as_bytes())
+            .map_err(SlidesError::FromNotesError)?;
+        let body = find_element(&html_dom.document, "body")
+            .ok_or(SlidesError::MissingBody)?;
+
+        let mut slide_map = HashMap::new();
+
+        Self::extract_slides_from(&body, &mut slide_map)?;
+
+        let slide_count = *slide_map.keys().max().unwrap_or(&0);
+        let mut slides = vec![String::from("<div></div>"); slide_count];
+
+        for (number, contents) in slide_map {
+            slides[number - 1] = format!("<div>{}</div>", contents);
+        }
+
+        Ok(Slides {
+            slides,
+            source: Some(notes.source_map()),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.slides.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slides.is_empty()
+    }
+
+    /// Returns the HTML of a slide, numbered from 1.
+    pub fn get(&self, slide_number: usize) -> Option<&str> {
+        slide_number
+            .checked_sub(1)
+            .and_then(|index| self.slides.get(index))
+            .map(String::as_str)
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        self.slides.iter().map(String::as_str)
+    }
+
+    fn extract_slides_from(
+        node: &Handle,
+        slide_map: &mut HashMap<usize, String>,
+    ) -> Result<(), SlidesError> {
+        if let NodeData::Element { ref attrs, .. } = node.data {
+            if let Some(slide_number) = Self::current_slide_of(&attrs.borrow())
+            {
+                if slide_number == 0 {
+                    return Err(SlidesError::ZeroSlideNumber);
+                }
+
+                if Self::last_slide_in(node) > slide_number {
+                    for (slide_number, part) in
+                        Self::split_by_slide(node, slide_number)
+                    {
+                        Self::append_to_slide(&part, slide_number, slide_map)?;
+                    }
+
+                    return Ok(());
+                }
+
+                return Self::append_to_slide(node, slide_number, slide_map);
+            }
+        }
+
+        for child in node.children.borrow().iter() {
+            Self::extract_slides_from(child, slide_map)?;
+        }
+
+        Ok(())
+    }
+
+    /// Splits a container whose descendants start new slides, like a heading
+    /// inside a block quote, into one copy of the container per slide.
+    fn split_by_slide(
+        node: &Handle,
+        slide_number: usize,
+    ) -> Vec<(usize, Handle)> {
+        let mut groups: Vec<(usize, Vec<Handle>)> = Vec::new();
+        let mut current_slide = slide_number;
+
+        for child in node.children.borrow().iter() {
+            let child_slide = match child.data {
+                NodeData::Element { ref attrs, .. } => {
+                    Self::current_slide_of(&attrs.borrow())
+                }
+                _ => None,
+            }
+            .unwrap_or(current_slide);
+            let last_child_slide = Self::last_slide_in(child);
+
+            let parts = if last_child_slide > child_slide {
+                Self::split_by_slide(child, child_slide)
+            } else {
+                vec![(child_slide, child.clone())]
+            };
+
+            for (part_slide, part) in parts {
+                match groups.last_mut() {
+                    Some((group_slide, group))
+                        if *group_slide == part_slide =>
+                    {
+                        group.push(part)
+                    }
+                    _ => groups.push((part_slide, vec![part])),
+                }
+            }
+
+            current_slide = current_slide.max(last_child_slide);
+        }
+
+        groups
+            .into_iter()
+            .filter(|(_, children)| !Self::is_blank(children))
+            .map(|(group_slide, children)| {
+                (group_slide, Self::copy_element(node, group_slide, children))
+            })
+            .collect()
+    }
+
+    fn is_blank(nodes: &[Handle]) -> bool {
+        nodes.iter().all(|node| {
+            matches!(
+                node.data,
+                NodeData::Text { ref contents }
+                    if contents.borrow().trim().is_empty()
+            )
+        })
+    }
+
+    fn copy_element(
+        node: &Handle,
+        slide_number: usize,
+        children: Vec<Handle>,
+    ) -> Handle {
+        let data = match node.data {
+            NodeData::Element {
+                ref name,
+                ref attrs,
+                ..
+            } => NodeData::Element {
+                name: name.clone(),
+                attrs: RefCell::new(Self::with_slide_class(
+                    &attrs.borrow(),
+                    slide_number,
+                )),
+                template_contents: None,
+                mathml_annotation_xml_integration_point: false,
+            },
+            _ => NodeData::Document,
+        };
+        let copy = Node::new(data);
+
+        *copy.children.borrow_mut() = children;
+
+        copy
+    }
+
+    fn append_to_slide(
+        node: &Handle,
+        slide_number: usize,
+        slide_map: &mut HashMap<usize, String>,
+    ) -> Result<(), SlidesError> {
+        let mut slide = Vec::new();
+        let options = SerializeOpts {
+            traversal_scope: TraversalScope::IncludeNode,
+            ..SerializeOpts::default()
+        };
+
+        serialize(&mut slide, &SerializableHandle::from(node.clone()), options)
+            .map_err(|source| SlidesError::SerializeSlide {
+                slide_number,
+                source,
+            })?;
+
+        let slide_string = String::from_utf8_lossy(&slide);
+
+        slide_map
+            .entry(slide_number)
+            .and_modify(|slide| slide.push_str(&slide_string))
+            .or_insert_with(|| slide_string.to_string());
+
+        Ok(())
+    }
+
+    fn last_slide_in(node: &Handle) -> usize {
+        let own_slide = match node.data {
+            NodeData::Element { ref attrs, .. } => {
+                Self::current_slide_of(&attrs.borrow())
+            }
+            _ => None,
+        };
+
+        node.children
+            .borrow()
+            .iter()
+            .map(Self::last_slide_in)
+            .chain(own_slide)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn with_slide_class(
+        attributes: &[Attribute],
+        slide_number: usize,
+    ) -> Vec<Attribute> {
+        let mut attributes = attributes.to_vec();
+
+        for attribute in &mut attributes {
+            if &attribute.name.local == "class" {
+                let classes: Vec<_> = attribute
+                    .value
+                    .split_whitespace()
+                    .map(|class_value| {
+                        if class_value.starts_with("slide-")
+                            && !class_value.starts_with("slide-step-")
+                        {
+                            format!("slide-{}", slide_number)
+                        } else {
+                            class_value.to_owned()
+                        }
+                    })
+                    .collect();
+
+                attribute.value = classes.join(" ").into();
+            }
+        }
+
+        attributes
+    }
+
+    fn current_slide_of(attributes: &[Attribute]) -> Option<usize> {
+        attributes
+            .iter()
+            .find(|attribute| &attribute.name.local == "class")
+            .and_then(|class_attribute| {
+                class_attribute
+                    .value
+                    .split_whitespace()
+                    .find(|class_value| {
+                        class_value.starts_with("slide-")
+                            && !class_value.starts_with("slide-step-")
+                    })
+                    .and_then(|slide_value| {
+                        let chars_to_skip = "slide-".len();
+                        let slide_number = &slide_value[chars_to_skip..];
+
+                        slide_number.parse().ok()
+                    })
+            })
+    }
+
+    pub fn manifest(&self) -> Result<Manifest, SlidesError> {
+        let mut slides = Vec::with_capacity(self.slides.len());
+        let mut polls = BTreeMap::new();
+
+        for slide in &self.slides {
+            let slide_dom =
+                parse_document(ArcDom::default(), ParseOpts::default())
+                    .from_utf8()
+                    .read_from(&mut slide.as_bytes())
+                    .map_err(SlidesError::ManifestError)?;
+
+            slides.push(Self::summarize(&slide_dom));
+            Poll::count_options(&slide_dom.document, &mut polls);
+        }
+
+        Ok(Manifest { slides, polls })
+    }
+
+    fn summarize(slide_dom: &ArcDom) -> SlideSummary {
+        let title = Self::find_title(&slide_dom.document)
+            .map(|heading| Self::text_of(&heading))
+            .unwrap_or_default();
+        let text = Self::text_of(&slide_dom.document);
+
+        SlideSummary { title, text }
+    }
+
+    fn find_title(node: &Handle) -> Option<Handle> {
+        if let NodeData::Element { ref name, .. } = node.data {
+            if &name.local == "h1"
+                || &name.local == "h2"
+                || &name.local == "h3"
+                || &name.local == "h4"
+                || &name.local == "h5"
+                || &name.local == "h6"
+            {
+                return Some(node.clone());
+            }
+        }
+
+        node.children.borrow().iter().find_map(Self::find_title)
+    }
+
+    fn text_of(node: &Handle) -> String {
+        let mut text = String::new();
+
+        Self::collect_text(node, &mut text);
+
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    fn collect_text(node: &Handle, text: &mut String) {
+        if let NodeData::Text { ref contents } = node.data {
+            text.push_str(&contents.borrow());
+            text.push(' ');
+        }
+
+        for child in node.children.borrow().iter() {
+            Self::collect_text(child, text);
+        }
+    }
+
+    pub fn step_spec_errors(
+        &self,
+    ) -> Result<Vec<(usize, StepSpecError)>, SlidesError> {
+        let mut errors = Vec::new();
+
+        for (slide_number, slide) in (1..).zip(&self.slides) {
+            let slide_dom = Self::parse_slide(slide)?;
+            let step_specs = Self::step_specs_of(&slide_dom.document);
+
+            errors.extend(
+                step_specs
+                    .into_iter()
+                    .filter_map(Result::err)
+                    .map(|error| (slide_number, error)),
+            );
+        }
+
+        Ok(errors)
+    }
+
+    pub fn lint(
+        &self,
+        options: &LintOptions,
+    ) -> Result<LintReport, SlidesError> {
+        let mut report = LintReport::new();
+
+        for (slide_number, slide) in (1..).zip(&self.slides) {
+            let slide_dom = Self::parse_slide(slide)?;
+            let step_specs = Self::step_specs_of(&slide_dom.document);
+
+            if Self::is_empty_slide(&slide_dom.document) {
+                report.push(Diagnostic::warning(
+                    DiagnosticKind::EmptySlide,
+                    self.locate(slide_number, None),
+                    "Slide has no content",
+                ));
+            }
+
+            for (index, step_spec) in step_specs.iter().enumerate() {
+                if let Err(error) = step_spec {
+                    report.push(Diagnostic::error(
+                        DiagnosticKind::InvalidStepSpec,
+                        self.locate(slide_number, Some(index)),
+                        error.to_string(),
+                    ));
+                }
+            }
+
+            let step_count = Self::step_count_of(&step_specs);
+            let missing_steps: Vec<_> = (1..=step_count)
+                .filter(|&step| {
+                    !step_specs
+                        .iter()
+                        .flatten()
+                        .flatten()
+                        .any(|range| range.contains(step))
+                })
+                .map(|step| step.to_string())
+                .collect();
+
+            if !missing_steps.is_empty() {
+                report.push(Diagnostic::warning(
+                    DiagnosticKind::StepGap,
+                    self.locate(slide_number, None),
+                    format!(
+                        "No element is shown in step(s) {}",
+                        missing_steps.join(", "),
+                    ),
+                ));
+            }
+
+            if step_count > options.max_steps {
+                report.push(Diagnostic::warning(
+                    DiagnosticKind::TooManySteps,
+                    self.locate(slide_number, None),
+                    format!(
+                        "Slide has {} steps, more than the limit of {}",
+                        step_count, options.max_steps,
+                    ),
+                ));
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Checks that every locale has the same slides and steps as the first
+    /// one.
+    pub fn lint_locales<'a>(
+        locales: impl IntoIterator<Item = (&'a str, &'a Slides)>,
+    ) -> Result<LintReport, SlidesError> {
+        let mut report = LintReport::new();
+        let mut locales = locales.into_iter();
+
+        let (reference_locale, reference_slides) = match locales.next() {
+            Some(reference) => reference,
+            None => return Ok(report),
+        };
+        let reference_steps = reference_slides.step_counts()?;
+
+        for (locale, slides) in locales {
+            let steps = slides.step_counts()?;
+
+            if steps.len() != reference_steps.len() {
+                let mut location = slides.locate(steps.len(), None);
+
+                location.slide = None;
+                location.line = None;
+                location.locale = Some(locale.to_owned());
+
+                report.push(Diagnostic::error(
+                    DiagnosticKind::LocaleMismatch,
+                    location,
+                    format!(
+                        "Locale has {} slides but {} has {}",
+                        steps.len(),
+                        reference_locale,
+                        reference_steps.len(),
+                    ),
+                ));
+            }
+
+            let slide_steps = (1..).zip(steps.iter().zip(&reference_steps));
+
+            for (slide_number, (&step_count, &reference_count)) in slide_steps {
+                if step_count != reference_count {
+                    let mut location = slides.locate(slide_number, None);
+
+                    location.locale = Some(locale.to_owned());
+
+                    report.push(Diagnostic::error(
+                        DiagnosticKind::LocaleMismatch,
+                        location,
+                        format!(
+                            "Slide has {} steps but it has {} in {}",
+                            step_count, reference_count, reference_locale,
+                        ),
+                    ));
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    fn step_counts(&self) -> Result<Vec<usize>, SlidesError> {
+        self.slides
+            .iter()
+            .map(|slide| {
+                let slide_dom = Self::parse_slide(slide)?;
+
+                Ok(Self::step_count_of(&Self::step_specs_of(
+                    &slide_dom.document,
+                )))
+            })
+            .collect()
+    }
+
+    fn step_count_of(step_specs: &[StepSpecResult]) -> usize {
+        step_specs
+            .iter()
+            .flatten()
+            .flatten()
+            .map(StepRange::last_known_step)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn locate(
+        &self,
+        slide_number: usize,
+        step_spec: Option<usize>,
+    ) -> Location {
+        match self.source {
+            Some(ref source) => source.locate(slide_number, step_spec),
+            None => Location {
+                slide: Some(slide_number),
+                ..Location::default()
+            },
+        }
+    }
+
+    fn parse_slide(slide: &str) -> Result<ArcDom, SlidesError> {
+        parse_document(ArcDom::default(), ParseOpts::default())
+            .from_utf8()
+            .read_from(&mut slide.as_bytes())
+            .map_err(SlidesError::ValidationError)
+    }
+
+    fn is_empty_slide(node: &Handle) -> bool {
+        if let NodeData::Element { ref name, .. } = node.data {
+            if &name.local == "img"
+                || &name.local == "svg"
+                || &name.local == "video"
+                || &name.local == "iframe"
+            {
+                return false;
+            }
+        }
+
+        if let NodeData::Text { ref contents } = node.data {
+            if !contents.borrow().trim().is_empty() {
+                return false;
+            }
+        }
+
+        node.children.borrow().iter().all(Self::is_empty_slide)
+    }
+
+    fn step_specs_of(node: &Handle) -> Vec<StepSpecResult> {
+        let mut context = StepContext::new();
+        let mut step_specs = Vec::new();
+
+        Self::collect_step_specs(node, &mut context, &mut step_specs);
+
+        step_specs
+    }
+
+    fn collect_step_specs(
+        node: &Handle,
+        context: &mut StepContext,
+        step_specs: &mut Vec<StepSpecResult>,
+    ) {
+        if let NodeData::Element { ref attrs, .. } = node.data {
+            let attrs = attrs.borrow();
+
+            if let Some(step_spec) = Self::attribute(&attrs, STEPS_ATTRIBUTE) {
+                let ranges = StepSpec::parse(step_spec.trim())
+                    .and_then(|spec| spec.resolve(context));
+
+                if let Ok(ref ranges) = ranges {
+                    context.advance(ranges);
+
+                    if let Some(label) =
+                        Self::attribute(&attrs, LABEL_ATTRIBUTE)
+                    {
+                        context.define_label(label.trim(), ranges);
+                    }
+                }
+
+                step_specs.push(ranges);
+            }
+        }
+
+        for child in node.children.borrow().iter() {
+            Self::collect_step_specs(child, context, step_specs);
+        }
+    }
+
+    fn attribute<'a>(
+        attributes: &'a [Attribute],
+        name: &str,
+    ) -> Option<&'a str> {
+        attributes
+            .iter()
+            .find(|attribute| &attribute.name.local == name)
+            .map(|attribute| &*attribute.value)
+    }
+
+    pub fn load_from<P: AsRef<Path>>(
+        &mut self,
+        directory: P,
+    ) -> Result<(), SlidesError> {
+        let files: Vec<_> = fs::read_dir(directory)
+            .and_then(|entries| entries.collect())
+            .map_err(SlidesError::SlidesDirectoryError)?;
+
+        let slide_files = files.iter().filter_map(Self::parse_slide_path);
+
+        for (slide_number, slide_file) in slide_files {
+            let slide_contents = fs::read_to_string(slide_file)
+                .map_err(SlidesError::LoadSlide)?;
+
+            if slide_number > self.slides.len() {
+                self.slides.resize(slide_number, String::new());
+            }
+
+            self.slides[slide_number - 1] = slide_contents;
+
+            if let Some(ref mut source) = self.source {
+                source.forget(slide_number);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_slide_path(dir_entry: &DirEntry) -> Option<(usize, PathBuf)> {
+        let path = dir_entry.path();
+
+        if path.extension() == Some(OsStr::new("html")) {
+            let slide_number = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().parse())
+                .and_then(Result::ok);
+
+            slide_number.map(|number| (number, path.to_owned()))
+        } else {
+            None
+        }
+    }
+
+    pub fn write_to<P: AsRef<Path>>(
+        &self,
+        output_dir: P,
+    ) -> Result<(), SlidesError> {
+        let output_dir = output_dir.as_ref();
+        for (slide_number, slide) in (1..).zip(&self.slides) {
+            let slide_path = output_dir.join(format!("{}.html", slide_number));
+
+            fs::write(slide_path, slide).map_err(SlidesError::WriteError)?;
+        }
+
+        let manifest = serde_json::to_string(&self.manifest()?)
+            .map_err(SlidesError::SerializeManifest)?;
+
+        fs::write(output_dir.join(Manifest::FILE_NAME), manifest)
+            .map_err(SlidesError::WriteError)
+    }
+}
+
+#[derive(Debug, Display, Error)]
+pub enum SlidesError {
+    #[display(fmt = "Failed to parse slides from notes")]
+    FromNotesError(io::Error),
+    #[display(fmt = "Notes have no body element to extract slides from")]
+    MissingBody,
+    #[display(fmt = "Slides are numbered from 1, but found a slide-0 class")]
+    ZeroSlideNumber,
+    #[display(
+        fmt = "Notes have no slides, were they split with `animate_steps`?"
+    )]
+    NoSlides,
+    #[display(fmt = "Failed to serialize slide {}", slide_number)]
+    SerializeSlide {
+        slide_number: usize,
+        source: io::Error,
+    },
+    #[display(fmt = "Failed to read slides from directory")]
+    SlidesDirectoryError(io::Error),
+    #[display(fmt = "Failed to read slide file")]
+    LoadSlide(io::Error),
+    #[display(fmt = "Failed to write slide")]
+    WriteError(io::Error),
+    #[display(fmt = "Failed to summarize slide for the manifest")]
+    ManifestError(io::Error),
+    #[display(fmt = "Failed to parse slide to validate it")]
+    ValidationError(io::Error),
+    #[display(fmt = "Failed to serialize slide manifest")]
+    SerializeManifest(serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::Slides, crate::pipeline::Notes};
+
+    fn slides_from(markdown: &str) -> Slides {
+        let mut notes = Notes::from_markdown_str(markdown);
+
+        notes.animate_steps().unwrap();
+
+        Slides::from_notes(&notes).unwrap()
+    }
+
+    #[test]
+    fn splits_slides_at_top_level_headings() {
+        let slides = slides_from("# One\n\nFirst\n\n# Two\n\nSecond\n");
+
+        assert_eq!(slides.len(), 2);
+        assert!(slides.get(1).unwrap().contains("First"));
+        assert!(!slides.get(1).unwrap().contains("Second"));
+        assert!(slides.get(2).unwrap().contains("Second"));
+    }
+
+    #[test]
+    fn splits_containers_with_nested_headings() {
+        let slides = slides_from(
+            "# One\n\n> Quote\n>\n> ## Two\n>\n> Inside\n\nAfter\n\n# Three\n",
+        );
+
+        assert_eq!(slides.len(), 3);
+
+        let (first, second) = (slides.get(1).unwrap(), slides.get(2).unwrap());
+
+        assert!(first.contains("<blockquote class=\"slide-1\">"));
+        assert!(first.contains("Quote"));
+        assert!(!first.contains("Two"));
+        assert!(!first.contains("Inside"));
+        assert!(second.contains("<blockquote class=\"slide-2\">"));
+        assert!(second.contains("<h2 class=\"slide-2\">Two</h2>"));
+        assert!(second.contains("Inside"));
+        assert!(second.contains("After"));
+        assert!(!second.contains("Quote"));
+        assert!(slides.get(3).unwrap().contains("Three"));
+    }
+
+    #[test]
+    fn splits_nested_lists_with_headings() {
+        let slides = slides_from("# One\n\n- Item\n\n  ## Two\n\n  More\n");
+
+        assert_eq!(slides.len(), 2);
+        assert!(slides.get(1).unwrap().contains("<ul class=\"slide-1\">"));
+        assert!(slides.get(1).unwrap().contains("Item"));
+        assert!(!slides.get(1).unwrap().contains("More"));
+        assert!(slides.get(2).unwrap().contains("<ul class=\"slide-2\">"));
+        assert!(slides.get(2).unwrap().contains("Two"));
+        assert!(slides.get(2).unwrap().contains("More"));
+    }
+}
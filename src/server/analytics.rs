@@ -0,0 +1,276 @@
+use {
+    crate::timeline::Timeline,
+    serde::Serialize,
+    std::{collections::BTreeMap, convert::TryInto, fmt::Write},
+};
+
+/// Where the time went during a presentation.
+#[derive(Clone, Debug, Serialize)]
+pub struct SessionReport {
+    /// When the first position was reached, in seconds since the Unix epoch.
+    pub started_at: u64,
+    /// How long the session lasted, in seconds.
+    pub duration: f64,
+    /// How many times the presenter went back to an earlier slide or step.
+    pub backward_jumps: usize,
+    /// The slides that were shown or have a time target, in order.
+    pub slides: Vec<SlideReport>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SlideReport {
+    pub slide: u16,
+    /// How long the slide was shown, in seconds.
+    pub time: f64,
+    /// How long the slide should have been shown, in seconds.
+    pub target: Option<f64>,
+    /// How many seconds the slide went over its target, or under it if
+    /// negative.
+    pub over_target: Option<f64>,
+    /// How many times the presenter arrived at the slide.
+    pub visits: usize,
+    pub steps: Vec<StepReport>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct StepReport {
+    pub step: u16,
+    /// How long the step was shown, in seconds.
+    pub time: f64,
+}
+
+impl SessionReport {
+    /// Measures a recorded timeline, with the last position lasting until
+    /// `duration` seconds after the first one.
+    ///
+    /// The time targets are in seconds, by slide number.
+    pub fn new(
+        timeline: &Timeline,
+        duration: f64,
+        targets: &BTreeMap<usize, f64>,
+    ) -> Self {
+        let mut slides: BTreeMap<u16, SlideReport> = BTreeMap::new();
+        let mut backward_jumps = 0;
+        let entries = &timeline.entries;
+
+        for (index, entry) in entries.iter().enumerate() {
+            let position = entry.position;
+            let previous = index
+                .checked_sub(1)
+                .map(|previous| entries[previous].position);
+            let end = entries
+                .get(index + 1)
+                .map_or(duration, |next| next.time)
+                .max(entry.time);
+            let time = end - entry.time;
+
+            let slide = slides
+                .entry(position.slide)
+                .or_insert_with(|| SlideReport::new(position.slide));
+
+            slide.time += time;
+
+            match slide
+                .steps
+                .iter_mut()
+                .find(|step| step.step == position.step)
+            {
+                Some(step) => step.time += time,
+                None => slide.steps.push(StepReport {
+                    step: position.step,
+                    time,
+                }),
+            }
+
+            if let Some(previous) = previous {
+                if (position.slide, position.step)
+                    < (previous.slide, previous.step)
+                {
+                    backward_jumps += 1;
+                }
+            }
+
+            if previous.map(|previous| previous.slide) != Some(position.slide) {
+                slide.visits += 1;
+            }
+        }
+
+        for (&slide, &target) in targets {
+            if let Ok(slide) = slide.try_into() {
+                let report = slides
+                    .entry(slide)
+                    .or_insert_with(|| SlideReport::new(slide));
+
+                report.target = Some(target);
+                report.over_target = Some(report.time - target);
+            }
+        }
+
+        let slides = slides
+            .into_values()
+            .map(|mut slide| {
+                slide.steps.sort_by_key(|step| step.step);
+                slide
+            })
+            .collect();
+
+        SessionReport {
+            started_at: timeline.started_at,
+            duration,
+            backward_jumps,
+            slides,
+        }
+    }
+
+    /// Formats the report as CSV, with a row for each slide.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("slide,time,target,over_target,visits\n");
+        let optional = |value: Option<f64>| {
+            value
+                .map(|value| format!("{:.1}", value))
+                .unwrap_or_default()
+        };
+
+        for slide in &self.slides {
+            // Writing to a `String` can't fail.
+            let _ = writeln!(
+                csv,
+                "{},{:.1},{},{},{}",
+                slide.slide,
+                slide.time,
+                optional(slide.target),
+                optional(slide.over_target),
+                slide.visits,
+            );
+        }
+
+        csv
+    }
+}
+
+impl SlideReport {
+    fn new(slide: u16) -> Self {
+        SlideReport {
+            slide,
+            time: 0.0,
+            target: None,
+            over_target: None,
+            visits: 0,
+            steps: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SessionReport;
+    use crate::{
+        sync_message::SyncPosition,
+        timeline::{Timeline, TimelineEntry},
+    };
+    use std::collections::BTreeMap;
+
+    fn timeline(entries: &[(f64, u16, u16)]) -> Timeline {
+        Timeline {
+            started_at: 1_000,
+            entries: entries
+                .iter()
+                .map(|&(time, slide, step)| TimelineEntry {
+                    time,
+                    position: SyncPosition { slide, step },
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn reports_nothing_for_an_empty_timeline() {
+        let report =
+            SessionReport::new(&Timeline::default(), 0.0, &BTreeMap::new());
+
+        assert_eq!(report.started_at, 0);
+        assert_eq!(report.duration, 0.0);
+        assert_eq!(report.backward_jumps, 0);
+        assert!(report.slides.is_empty());
+        assert_eq!(report.to_csv(), "slide,time,target,over_target,visits\n");
+    }
+
+    #[test]
+    fn measures_slides_and_steps_until_the_end_of_the_session() {
+        let timeline = timeline(&[(0.0, 0, 0), (2.0, 0, 1), (3.0, 1, 0)]);
+        let report = SessionReport::new(&timeline, 7.5, &BTreeMap::new());
+
+        assert_eq!(report.started_at, 1_000);
+        assert_eq!(report.slides.len(), 2);
+        assert_eq!(report.slides[0].time, 3.0);
+        assert_eq!(report.slides[0].visits, 1);
+        assert_eq!(report.slides[0].steps.len(), 2);
+        assert_eq!(report.slides[0].steps[0].time, 2.0);
+        assert_eq!(report.slides[0].steps[1].time, 1.0);
+        assert_eq!(report.slides[1].time, 4.5);
+        assert_eq!(report.slides[1].visits, 1);
+    }
+
+    #[test]
+    fn counts_backward_jumps_and_revisits() {
+        let timeline = timeline(&[
+            (0.0, 0, 0),
+            (1.0, 0, 1),
+            (2.0, 0, 0),
+            (3.0, 1, 0),
+            (4.0, 0, 1),
+            (5.0, 1, 0),
+        ]);
+        let report = SessionReport::new(&timeline, 6.0, &BTreeMap::new());
+
+        assert_eq!(report.backward_jumps, 2);
+        assert_eq!(report.slides[0].visits, 2);
+        assert_eq!(report.slides[0].time, 4.0);
+        assert_eq!(report.slides[0].steps[0].time, 2.0);
+        assert_eq!(report.slides[0].steps[1].time, 2.0);
+        assert_eq!(report.slides[1].visits, 2);
+        assert_eq!(report.slides[1].time, 2.0);
+    }
+
+    #[test]
+    fn reports_targets_of_slides_that_were_never_shown() {
+        let timeline = timeline(&[(0.0, 0, 0)]);
+        let targets = vec![(0, 5.0), (2, 30.0)].into_iter().collect();
+        let report = SessionReport::new(&timeline, 8.0, &targets);
+
+        assert_eq!(report.slides.len(), 2);
+        assert_eq!(report.slides[0].over_target, Some(3.0));
+        assert_eq!(report.slides[1].slide, 2);
+        assert_eq!(report.slides[1].time, 0.0);
+        assert_eq!(report.slides[1].visits, 0);
+        assert!(report.slides[1].steps.is_empty());
+        assert_eq!(report.slides[1].target, Some(30.0));
+        assert_eq!(report.slides[1].over_target, Some(-30.0));
+        assert_eq!(
+            report.to_csv(),
+            "slide,time,target,over_target,visits\n\
+             0,8.0,5.0,3.0,1\n\
+             2,0.0,30.0,-30.0,0\n",
+        );
+    }
+
+    #[test]
+    fn ignores_targets_of_slides_beyond_the_position_range() {
+        let targets = vec![(usize::from(u16::MAX) + 1, 10.0)]
+            .into_iter()
+            .collect();
+        let report = SessionReport::new(&Timeline::default(), 0.0, &targets);
+
+        assert!(report.slides.is_empty());
+    }
+
+    #[test]
+    fn never_counts_negative_time_when_the_session_ends_early() {
+        let timeline = timeline(&[(0.0, 0, 0), (4.0, 1, 0)]);
+        let report = SessionReport::new(&timeline, 2.0, &BTreeMap::new());
+
+        assert_eq!(report.slides[0].time, 4.0);
+        assert_eq!(report.slides[1].time, 0.0);
+        assert_eq!(report.to_csv().lines().nth(2), Some("1,0.0,,,1"));
+    }
+}
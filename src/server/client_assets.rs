@@ -0,0 +1,105 @@
+use {
+    super::deck::{Deck, FileResponse},
+    axum::{
+        extract::Extension,
+        http::{header, StatusCode, Uri},
+        response::{Headers, IntoResponse},
+    },
+    std::{borrow::Cow, fs, iter, path::PathBuf, sync::Arc},
+};
+
+/// The default shell page, loading the client from the paths below.
+const INDEX_HTML: &str = include_str!("../../index.html");
+
+/// The client built with `wasm-bindgen --target web --out-name presentrs`,
+/// with its output directory set in `PRESENTRS_CLIENT_DIR` at build time.
+///
+/// Without it, only the shell page is bundled, and the client is served from
+/// the overlay directory.
+#[cfg(presentrs_client_dir)]
+const CLIENT_FILES: [(&str, &[u8]); 2] = [
+    (
+        "presentrs.js",
+        include_bytes!(concat!(
+            env!("PRESENTRS_EMBEDDED_CLIENT_DIR"),
+            "/presentrs.js"
+        )),
+    ),
+    (
+        "presentrs_bg.wasm",
+        include_bytes!(concat!(
+            env!("PRESENTRS_EMBEDDED_CLIENT_DIR"),
+            "/presentrs_bg.wasm"
+        )),
+    ),
+];
+#[cfg(not(presentrs_client_dir))]
+const CLIENT_FILES: [(&str, &[u8]); 0] = [];
+
+/// The shell page and client assets bundled into the server, which can be
+/// overridden by files with the same names in the overlay directory.
+pub struct ClientAssets {
+    overlay: Option<PathBuf>,
+    assets: Deck,
+}
+
+impl ClientAssets {
+    pub const PATHS: [&'static str; 4] =
+        ["/", "/index.html", "/presentrs.js", "/presentrs_bg.wasm"];
+
+    pub fn new(overlay: Option<PathBuf>) -> Arc<Self> {
+        Arc::new(ClientAssets {
+            overlay,
+            assets: Self::add_to(Deck::new()),
+        })
+    }
+
+    /// Adds the bundled assets to a deck, keeping any files it already has.
+    pub fn add_to(mut deck: Deck) -> Deck {
+        let assets = iter::once(("index.html", INDEX_HTML.as_bytes()))
+            .chain(CLIENT_FILES.iter().copied());
+
+        for (path, contents) in assets {
+            if deck.get(path).is_none() {
+                deck.add_file(path, Cow::Borrowed(contents));
+            }
+        }
+
+        deck
+    }
+
+    pub async fn handler(
+        uri: Uri,
+        Extension(client_assets): Extension<Arc<ClientAssets>>,
+    ) -> impl IntoResponse {
+        client_assets.serve(uri.path())
+    }
+
+    fn serve(&self, path: &str) -> Result<FileResponse, StatusCode> {
+        let file_path = match path.trim_start_matches('/') {
+            "" => "index.html",
+            file_path => file_path,
+        };
+        let overlay_file = self
+            .overlay
+            .as_ref()
+            .map(|overlay| overlay.join(file_path))
+            .filter(|overlay_file| overlay_file.is_file());
+
+        match overlay_file {
+            Some(overlay_file) => {
+                let contents = fs::read(&overlay_file)
+                    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+                let content_type = mime_guess::from_path(&overlay_file)
+                    .first_or_octet_stream()
+                    .to_string();
+
+                Ok((
+                    Headers(vec![(header::CONTENT_TYPE, content_type)]),
+                    contents,
+                ))
+            }
+            None => self.assets.serve(path),
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use {
+    crate::{
+        manifest::Manifest,
+        pipeline::{Notes, Slides, SlidesError},
+    },
+    axum::{
+        extract::Extension,
+        http::{header, header::HeaderName, StatusCode, Uri},
+        response::{Headers, IntoResponse},
+    },
+    std::{
+        borrow::Cow,
+        collections::{BTreeMap, HashMap},
+        fs, iter,
+        path::{Path, PathBuf},
+        sync::Arc,
+    },
+};
+
+pub(crate) type FileResponse = (Headers<Vec<(HeaderName, String)>>, Vec<u8>);
+
+/// A deck kept entirely in memory, served without writing any files.
+#[derive(Clone, Debug, Default)]
+pub struct Deck {
+    files: HashMap<String, DeckFile>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DeckFile {
+    content_type: String,
+    contents: Cow<'static, [u8]>,
+}
+
+impl Deck {
+    pub fn new() -> Self {
+        Deck::default()
+    }
+
+    /// Builds a deck from notes already split with `Notes::animate_steps`.
+    ///
+    /// Fails with `SlidesError::NoSlides` if the notes have no slides, so that
+    /// a deck is never served empty by mistake.
+    pub fn from_notes(notes: &Notes) -> Result<Self, SlidesError> {
+        let slides = Slides::from_notes(notes)?;
+
+        if slides.is_empty() {
+            return Err(SlidesError::NoSlides);
+        }
+
+        Deck::new().with_notes(notes).with_slides(None, &slides)
+    }
+
+    pub fn with_notes(self, notes: &Notes) -> Self {
+        let deck =
+            self.with_file("notes.html", notes.html().as_bytes().to_vec());
+
+        match notes.css() {
+            Some(css) => deck.with_file("notes.css", css.as_bytes().to_vec()),
+            None => deck,
+        }
+    }
+
+    pub fn with_slides(
+        mut self,
+        locale: Option<&str>,
+        slides: &Slides,
+    ) -> Result<Self, SlidesError> {
+        let directory = match locale {
+            Some(locale) => format!("slides/{}/", locale),
+            None => "slides/".to_owned(),
+        };
+
+        for (slide_number, slide) in (1..).zip(slides) {
+            self.add_file(
+                format!("{}{}.html", directory, slide_number),
+                slide.as_bytes().to_vec(),
+            );
+        }
+
+        let manifest = serde_json::to_string(&slides.manifest()?)
+            .map_err(SlidesError::SerializeManifest)?;
+
+        self.add_file(
+            format!("{}{}", directory, Manifest::FILE_NAME),
+            manifest.into_bytes(),
+        );
+
+        Ok(self)
+    }
+
+    pub fn with_file(
+        mut self,
+        path: impl Into<String>,
+        contents: impl Into<Cow<'static, [u8]>>,
+    ) -> Self {
+        self.add_file(path, contents);
+        self
+    }
+
+    pub fn add_file(
+        &mut self,
+        path: impl Into<String>,
+        contents: impl Into<Cow<'static, [u8]>>,
+    ) {
+        let path = path.into();
+        let content_type = mime_guess::from_path(&path)
+            .first_or_octet_stream()
+            .to_string();
+
+        self.files.insert(
+            path,
+            DeckFile {
+                content_type,
+                contents: contents.into(),
+            },
+        );
+    }
+
+    /// The polls of the deck's slides in every locale, by poll number, with
+    /// how many options each has.
+    pub fn polls(&self) -> BTreeMap<u32, u32> {
+        self.files
+            .iter()
+            .filter(|(path, _)| Self::is_slide_manifest(Path::new(path)))
+            .filter_map(|(_, file)| serde_json::from_slice(&file.contents).ok())
+            .flat_map(|manifest: Manifest| manifest.polls)
+            .collect()
+    }
+
+    /// Reads the polls from the slide manifests of a deck directory, like
+    /// `Deck::polls`.
+    pub(crate) fn polls_in(directory: &Path) -> BTreeMap<u32, u32> {
+        let slides = directory.join("slides");
+        let locales = fs::read_dir(&slides)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir());
+
+        iter::once(slides.clone())
+            .chain(locales)
+            .filter_map(|path| {
+                fs::read_to_string(path.join(Manifest::FILE_NAME)).ok()
+            })
+            .filter_map(|manifest| serde_json::from_str(&manifest).ok())
+            .flat_map(|manifest: Manifest| manifest.polls)
+            .collect()
+    }
+
+    fn is_slide_manifest(path: &Path) -> bool {
+        path.starts_with("slides")
+            && path.file_name() == Some(Manifest::FILE_NAME.as_ref())
+    }
+
+    pub fn get(&self, path: &str) -> Option<&DeckFile> {
+        let path = path.trim_start_matches('/');
+
+        if path.is_empty() || path.ends_with('/') {
+            self.files.get(&format!("{}index.html", path))
+        } else {
+            self.files.get(path)
+        }
+    }
+
+    pub(crate) async fn handler(
+        uri: Uri,
+        Extension(deck): Extension<Arc<Deck>>,
+    ) -> impl IntoResponse {
+        deck.serve(uri.path())
+    }
+
+    pub(crate) fn serve(&self, path: &str) -> Result<FileResponse, StatusCode> {
+        match self.get(path) {
+            Some(file) => Ok((
+                Headers(vec![(
+                    header::CONTENT_TYPE,
+                    file.content_type.clone(),
+                )]),
+                file.contents.clone().into_owned(),
+            )),
+            None => Err(StatusCode::NOT_FOUND),
+        }
+    }
+}
+
+impl DeckFile {
+    pub fn content_type(&self) -> &str {
+        &self.content_type
+    }
+
+    pub fn contents(&self) -> &[u8] {
+        &self.contents
+    }
+}
+
+/// Where `Presentrs` serves a deck from.
+pub enum DeckSource {
+    Directory(PathBuf),
+    InMemory(Arc<Deck>),
+}
+
+impl From<Deck> for DeckSource {
+    fn from(deck: Deck) -> Self {
+        DeckSource::InMemory(Arc::new(deck))
+    }
+}
+
+impl From<Arc<Deck>> for DeckSource {
+    fn from(deck: Arc<Deck>) -> Self {
+        DeckSource::InMemory(deck)
+    }
+}
+
+impl From<PathBuf> for DeckSource {
+    fn from(path: PathBuf) -> Self {
+        DeckSource::Directory(path)
+    }
+}
+
+impl From<&Path> for DeckSource {
+    fn from(path: &Path) -> Self {
+        DeckSource::Directory(path.to_owned())
+    }
+}
+
+impl From<&str> for DeckSource {
+    fn from(path: &str) -> Self {
+        DeckSource::Directory(path.into())
+    }
+}
+
+impl From<String> for DeckSource {
+    fn from(path: String) -> Self {
+        DeckSource::Directory(path.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::Deck,
+        crate::pipeline::{Notes, SlidesError},
+    };
+
+    #[test]
+    fn builds_slides_from_animated_notes() {
+        let mut notes = Notes::from_markdown_str("# One\n\n# Two\n");
+
+        notes.animate_steps().unwrap();
+
+        let deck = Deck::from_notes(&notes).unwrap();
+
+        assert!(deck.get("notes.html").is_some());
+        assert!(deck.get("slides/2.html").is_some());
+        assert!(deck.get("slides/manifest.json").is_some());
+    }
+
+    #[test]
+    fn refuses_notes_without_slides() {
+        let notes = Notes::from_markdown_str("# One\n\n# Two\n");
+
+        assert!(matches!(
+            Deck::from_notes(&notes),
+            Err(SlidesError::NoSlides)
+        ));
+    }
+}
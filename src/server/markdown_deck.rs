@@ -0,0 +1,153 @@
+use {
+    super::{deck::Deck, slide_presenter::SlidePresenter},
+    crate::pipeline::{Notes, NotesError, SlidesError},
+    axum::{
+        extract::Extension,
+        http::{StatusCode, Uri},
+        response::IntoResponse,
+    },
+    derive_more::{Display, Error, From},
+    std::{fs, io, path::PathBuf, sync::Arc, time::SystemTime},
+    tokio::{
+        sync::Mutex,
+        task::{self, JoinError},
+    },
+    tracing::{error, info},
+};
+
+/// Builds a deck from a markdown file on request, rebuilding it whenever the
+/// file's modification time changes.
+///
+/// The markdown file provides the slides for every locale, so the slides are
+/// also served from any `slides/<locale>/` directory.
+pub struct MarkdownDeck {
+    markdown_file: PathBuf,
+    presenter: Option<Arc<SlidePresenter>>,
+    cache: Mutex<Option<CachedDeck>>,
+}
+
+struct CachedDeck {
+    modified: SystemTime,
+    deck: Arc<Deck>,
+}
+
+impl MarkdownDeck {
+    pub fn new(markdown_file: impl Into<PathBuf>) -> Arc<Self> {
+        Arc::new(MarkdownDeck {
+            markdown_file: markdown_file.into(),
+            presenter: None,
+            cache: Mutex::new(None),
+        })
+    }
+
+    /// Builds a deck from a markdown file, telling the presenter about the
+    /// polls of the deck whenever it is rebuilt.
+    pub fn with_presenter(
+        markdown_file: impl Into<PathBuf>,
+        presenter: Arc<SlidePresenter>,
+    ) -> Arc<Self> {
+        Arc::new(MarkdownDeck {
+            markdown_file: markdown_file.into(),
+            presenter: Some(presenter),
+            cache: Mutex::new(None),
+        })
+    }
+
+    pub async fn handler(
+        uri: Uri,
+        Extension(markdown_deck): Extension<Arc<MarkdownDeck>>,
+    ) -> impl IntoResponse {
+        match markdown_deck.deck().await {
+            Ok(deck) => deck.serve(&Self::unlocalized(uri.path())),
+            Err(build_error) => {
+                error!(
+                    "Failed to build deck from {}: {}",
+                    markdown_deck.markdown_file.display(),
+                    build_error,
+                );
+
+                Err(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        }
+    }
+
+    /// Returns the deck, rebuilding it on a blocking thread if the markdown
+    /// file changed since it was last built.
+    pub async fn deck(&self) -> Result<Arc<Deck>, MarkdownDeckError> {
+        let mut cache = self.cache.lock().await;
+        let markdown_file = self.markdown_file.clone();
+        let cached = cache
+            .as_ref()
+            .map(|cached| (cached.modified, cached.deck.clone()));
+
+        let (modified, deck) =
+            task::spawn_blocking(move || Self::build(markdown_file, cached))
+                .await
+                .map_err(MarkdownDeckError::Build)??;
+
+        let rebuilt = !matches!(
+            *cache,
+            Some(ref cached) if Arc::ptr_eq(&cached.deck, &deck)
+        );
+
+        if let (true, Some(presenter)) = (rebuilt, &self.presenter) {
+            presenter.set_polls(deck.polls());
+        }
+
+        *cache = Some(CachedDeck {
+            modified,
+            deck: deck.clone(),
+        });
+
+        Ok(deck)
+    }
+
+    fn build(
+        markdown_file: PathBuf,
+        cached: Option<(SystemTime, Arc<Deck>)>,
+    ) -> Result<(SystemTime, Arc<Deck>), MarkdownDeckError> {
+        let modified = fs::metadata(&markdown_file)
+            .and_then(|metadata| metadata.modified())
+            .map_err(MarkdownDeckError::Metadata)?;
+
+        if let Some((cached_modified, deck)) = cached {
+            if cached_modified == modified {
+                return Ok((modified, deck));
+            }
+        }
+
+        info!("Building deck from {}", markdown_file.display());
+
+        let mut notes = Notes::from_markdown(&markdown_file)?;
+
+        notes.animate_steps()?;
+
+        Ok((modified, Arc::new(Deck::from_notes(&notes)?)))
+    }
+
+    fn unlocalized(path: &str) -> String {
+        let path = path.trim_start_matches('/');
+
+        match path
+            .strip_prefix("slides/")
+            .map(|file| file.split_once('/'))
+        {
+            Some(Some((_locale, file))) => format!("slides/{}", file),
+            _ => path.to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Display, Error, From)]
+pub enum MarkdownDeckError {
+    #[display(fmt = "Failed to check the markdown file's modification time")]
+    #[from(ignore)]
+    Metadata(io::Error),
+    #[display(fmt = "Failed to build notes from markdown")]
+    Notes(NotesError),
+    #[display(fmt = "Failed to build slides from notes")]
+    Slides(SlidesError),
+    #[display(fmt = "Building the deck was interrupted")]
+    #[from(ignore)]
+    Build(JoinError),
+}
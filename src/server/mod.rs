@@ -1,10 +1,24 @@
-mod notes;
+mod analytics;
+#[cfg(feature = "embedded-client")]
+mod client_assets;
+mod deck;
+mod markdown_deck;
+mod polls;
 mod presentrs;
+mod questions;
+mod reactions;
+mod roster;
 mod slide_presenter;
-mod slides;
+mod timeline_recorder;
+
+#[cfg(feature = "embedded-client")]
+pub use self::client_assets::ClientAssets;
 
 pub use self::{
-    notes::{Notes, NotesError},
+    analytics::{SessionReport, SlideReport, StepReport},
+    deck::{Deck, DeckFile, DeckSource},
+    markdown_deck::{MarkdownDeck, MarkdownDeckError},
     presentrs::Presentrs,
-    slides::{Slides, SlidesError},
+    roster::{RosterEntry, RosterReport},
+    slide_presenter::{SlidePresenter, SyncOptions},
 };
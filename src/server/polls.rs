@@ -0,0 +1,102 @@
+use {
+    super::roster::Voter,
+    crate::sync_message::PollResults,
+    std::collections::{BTreeMap, HashMap},
+};
+
+/// The votes cast in the polls of a presentation, one per voter and poll.
+#[derive(Debug, Default)]
+pub(crate) struct Polls {
+    options: BTreeMap<u32, u32>,
+    votes: HashMap<u32, HashMap<Voter, u32>>,
+}
+
+impl Polls {
+    /// Sets the polls of the deck, by poll number, with how many options
+    /// each has.
+    pub fn set_options(&mut self, options: BTreeMap<u32, u32>) {
+        self.options = options;
+    }
+
+    /// Records a vote, replacing the voter's previous vote in the poll.
+    ///
+    /// Returns the updated results, or `None` if the deck has no such poll or
+    /// option.
+    pub fn vote(
+        &mut self,
+        poll: u32,
+        voter: Voter,
+        option: u32,
+    ) -> Option<PollResults> {
+        if option >= *self.options.get(&poll)? {
+            return None;
+        }
+
+        self.votes.entry(poll).or_default().insert(voter, option);
+
+        Some(self.results(poll))
+    }
+
+    pub fn results(&self, poll: u32) -> PollResults {
+        let option_count = self.options.get(&poll).copied().unwrap_or(0);
+        let mut votes = vec![0; option_count as usize];
+
+        for &option in self
+            .votes
+            .get(&poll)
+            .into_iter()
+            .flat_map(|votes| votes.values())
+        {
+            if let Some(count) = votes.get_mut(option as usize) {
+                *count += 1;
+            }
+        }
+
+        PollResults { poll, votes }
+    }
+
+    /// The results of every poll that has votes, ordered by poll.
+    pub fn all_results(&self) -> Vec<PollResults> {
+        let mut polls: Vec<_> = self.votes.keys().copied().collect();
+
+        polls.sort_unstable();
+        polls.into_iter().map(|poll| self.results(poll)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{Polls, Voter},
+        std::{collections::BTreeMap, iter},
+    };
+
+    fn polls() -> Polls {
+        let mut polls = Polls::default();
+
+        polls.set_options(iter::once((1, 3)).collect::<BTreeMap<_, _>>());
+        polls
+    }
+
+    #[test]
+    fn counts_one_vote_per_voter() {
+        let mut polls = polls();
+        let voter = Voter::Client("phone".to_owned());
+
+        polls.vote(1, voter.clone(), 0).unwrap();
+        polls.vote(1, Voter::Connection(7), 2).unwrap();
+
+        let results = polls.vote(1, voter, 1).unwrap();
+
+        assert_eq!(results.votes, vec![0, 1, 1]);
+    }
+
+    #[test]
+    fn rejects_unknown_polls_and_options() {
+        let mut polls = polls();
+
+        assert!(polls.vote(2, Voter::Connection(1), 0).is_none());
+        assert!(polls.vote(1, Voter::Connection(1), 3).is_none());
+        assert!(polls.all_results().is_empty());
+    }
+}
@@ -1,17 +1,102 @@
+#[cfg(feature = "embedded-client")]
+use super::client_assets::ClientAssets;
+
 use {
-    super::slide_presenter::SlidePresenter,
+    super::{
+        deck::{Deck, DeckSource},
+        markdown_deck::MarkdownDeck,
+        slide_presenter::SlidePresenter,
+    },
     axum::{
         handler, http::StatusCode, routing::BoxRoute, service,
         AddExtensionLayer, Router,
     },
-    std::{convert::Infallible, io, path::Path},
+    std::{
+        convert::Infallible,
+        io,
+        path::{Path, PathBuf},
+        sync::Arc,
+    },
     tower_http::services::ServeDir,
 };
 
 pub struct Presentrs;
 
 impl Presentrs {
-    pub fn new(path: impl AsRef<Path>) -> Router<BoxRoute> {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(deck: impl Into<DeckSource>) -> Router<BoxRoute> {
+        Self::new_with_presenter(deck, SlidePresenter::new())
+    }
+
+    /// Serves a deck, synchronizing its clients through the given presenter
+    /// so that they can be shut down with it.
+    pub fn new_with_presenter(
+        deck: impl Into<DeckSource>,
+        presenter: Arc<SlidePresenter>,
+    ) -> Router<BoxRoute> {
+        let files = match deck.into() {
+            DeckSource::Directory(path) => {
+                presenter.set_polls(Deck::polls_in(&path));
+
+                Self::with_client_assets(
+                    Self::serve_directory(path.clone()),
+                    path,
+                )
+            }
+            DeckSource::InMemory(deck) => {
+                presenter.set_polls(deck.polls());
+
+                #[cfg(feature = "embedded-client")]
+                let deck = Arc::new(ClientAssets::add_to(Deck::clone(&deck)));
+
+                Router::new()
+                    .nest("/", handler::get(Deck::handler))
+                    .layer(AddExtensionLayer::new(deck))
+                    .boxed()
+            }
+        };
+
+        Self::with_sync(files, presenter)
+    }
+
+    /// Serves the notes and slides built from a markdown file, along with the
+    /// other files in the assets directory.
+    ///
+    /// The deck is rebuilt on request whenever the markdown file changes.
+    pub fn from_markdown(
+        markdown_file: impl Into<PathBuf>,
+        assets: impl AsRef<Path>,
+    ) -> Router<BoxRoute> {
+        Self::from_markdown_with_presenter(
+            markdown_file,
+            assets,
+            SlidePresenter::new(),
+        )
+    }
+
+    /// Serves a deck built from a markdown file, synchronizing its clients
+    /// through the given presenter.
+    pub fn from_markdown_with_presenter(
+        markdown_file: impl Into<PathBuf>,
+        assets: impl AsRef<Path>,
+        presenter: Arc<SlidePresenter>,
+    ) -> Router<BoxRoute> {
+        let markdown_deck =
+            MarkdownDeck::with_presenter(markdown_file, presenter.clone());
+        let assets = assets.as_ref().to_owned();
+        let files = Self::serve_directory(assets.clone());
+        let files = Self::with_client_assets(files, assets)
+            .route("/notes.html", handler::get(MarkdownDeck::handler))
+            .route("/notes.css", handler::get(MarkdownDeck::handler))
+            .route("/slides/:file", handler::get(MarkdownDeck::handler))
+            .route("/slides/:locale/:file", handler::get(MarkdownDeck::handler))
+            .layer(AddExtensionLayer::new(markdown_deck))
+            .boxed();
+
+        Self::with_sync(files, presenter)
+    }
+
+    fn serve_directory(path: PathBuf) -> Router<BoxRoute> {
         Router::new()
             .nest(
                 "/",
@@ -19,13 +104,59 @@ impl Presentrs {
                     |_error: io::Error| -> Result<_, Infallible> {
                         Ok((
                             StatusCode::INTERNAL_SERVER_ERROR,
-                            format!("Failed to serve file"),
+                            "Failed to serve file".to_owned(),
                         ))
                     },
                 ),
             )
+            .boxed()
+    }
+
+    /// Serves the bundled shell page and client unless the directory has its
+    /// own versions of them.
+    #[cfg(feature = "embedded-client")]
+    fn with_client_assets(
+        mut files: Router<BoxRoute>,
+        overlay: PathBuf,
+    ) -> Router<BoxRoute> {
+        for path in &ClientAssets::PATHS {
+            files = files
+                .route(path, handler::get(ClientAssets::handler))
+                .boxed();
+        }
+
+        files
+            .layer(AddExtensionLayer::new(ClientAssets::new(Some(overlay))))
+            .boxed()
+    }
+
+    #[cfg(not(feature = "embedded-client"))]
+    fn with_client_assets(
+        files: Router<BoxRoute>,
+        _overlay: PathBuf,
+    ) -> Router<BoxRoute> {
+        files
+    }
+
+    fn with_sync(
+        files: Router<BoxRoute>,
+        presenter: Arc<SlidePresenter>,
+    ) -> Router<BoxRoute> {
+        files
             .route("/sync", handler::get(SlidePresenter::handler))
-            .layer(AddExtensionLayer::new(SlidePresenter::new()))
+            .route(
+                "/sync/clients",
+                handler::get(SlidePresenter::clients_handler),
+            )
+            .route(
+                "/sync/timeline",
+                handler::get(SlidePresenter::timeline_handler),
+            )
+            .route(
+                "/sync/analytics",
+                handler::get(SlidePresenter::analytics_handler),
+            )
+            .layer(AddExtensionLayer::new(presenter))
             .boxed()
     }
 }
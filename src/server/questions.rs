@@ -0,0 +1,182 @@
+use {
+    super::roster::Voter,
+    crate::sync_message::Question,
+    std::{
+        cmp::Reverse,
+        collections::{HashMap, HashSet},
+    },
+};
+
+/// The longest question that can be asked, in characters.
+const MAX_QUESTION_LENGTH: usize = 500;
+
+/// How many questions can be waiting before new ones are refused.
+const MAX_QUESTIONS: usize = 200;
+
+/// How many of the waiting questions a client can have asked, so that one
+/// client can't fill the queue.
+const MAX_QUESTIONS_PER_CLIENT: usize = 5;
+
+/// The Q&A queue of a presentation.
+///
+/// The audience asks questions, and presenters upvote the ones they want to
+/// answer and dismiss them once they are answered.
+#[derive(Debug, Default)]
+pub(crate) struct Questions {
+    questions: Vec<Question>,
+    askers: HashMap<u32, Voter>,
+    upvoters: HashMap<u32, HashSet<Voter>>,
+    next_id: u32,
+}
+
+impl Questions {
+    /// Adds a question to the end of the queue.
+    ///
+    /// Returns `false` if the question is empty, too long, the queue is
+    /// full, or the asker already has too many questions waiting.
+    pub fn ask(&mut self, text: &str, asker: Voter) -> bool {
+        let text = text.trim();
+        let asked = self
+            .askers
+            .values()
+            .filter(|previous_asker| **previous_asker == asker)
+            .count();
+
+        if text.is_empty()
+            || text.chars().count() > MAX_QUESTION_LENGTH
+            || self.questions.len() >= MAX_QUESTIONS
+            || asked >= MAX_QUESTIONS_PER_CLIENT
+        {
+            return false;
+        }
+
+        self.questions.push(Question {
+            id: self.next_id,
+            text: text.to_owned(),
+            votes: 0,
+        });
+        self.askers.insert(self.next_id, asker);
+        self.next_id = self.next_id.wrapping_add(1);
+
+        true
+    }
+
+    /// Stops counting the questions of a client that disconnected against
+    /// its limit, unless it can be recognized when it reconnects.
+    pub fn leave(&mut self, id: usize) {
+        self.askers
+            .retain(|_, asker| *asker != Voter::Connection(id));
+    }
+
+    /// Upvotes a question once per voter.
+    ///
+    /// Returns `false` if there is no such question, or the voter already
+    /// upvoted it.
+    pub fn upvote(&mut self, id: u32, voter: Voter) -> bool {
+        let question = match self
+            .questions
+            .iter_mut()
+            .find(|question| question.id == id)
+        {
+            Some(question) => question,
+            None => return false,
+        };
+
+        if !self.upvoters.entry(id).or_default().insert(voter) {
+            return false;
+        }
+
+        question.votes += 1;
+
+        true
+    }
+
+    pub fn dismiss(&mut self, id: u32) -> bool {
+        let count = self.questions.len();
+
+        self.questions.retain(|question| question.id != id);
+        self.askers.remove(&id);
+        self.upvoters.remove(&id);
+
+        self.questions.len() != count
+    }
+
+    /// The waiting questions, the most upvoted first and then in the order
+    /// they were asked.
+    pub fn list(&self) -> Vec<Question> {
+        let mut questions = self.questions.clone();
+
+        questions.sort_by_key(|question| Reverse(question.votes));
+
+        questions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Questions, Voter, MAX_QUESTIONS_PER_CLIENT};
+
+    #[test]
+    fn counts_one_upvote_per_voter() {
+        let mut questions = Questions::default();
+
+        assert!(questions.ask("Why?", Voter::Connection(1)));
+        assert!(questions.ask("How?", Voter::Connection(2)));
+
+        assert!(questions.upvote(1, Voter::Client("laptop".to_owned())));
+        assert!(!questions.upvote(1, Voter::Client("laptop".to_owned())));
+        assert!(questions.upvote(1, Voter::Connection(3)));
+        assert!(!questions.upvote(2, Voter::Connection(3)));
+
+        let list = questions.list();
+
+        assert_eq!(list[0].text, "How?");
+        assert_eq!(list[0].votes, 2);
+        assert_eq!(list[1].votes, 0);
+    }
+
+    #[test]
+    fn forgets_the_upvotes_of_dismissed_questions() {
+        let mut questions = Questions::default();
+
+        questions.ask("Why?", Voter::Connection(2));
+        questions.upvote(0, Voter::Connection(1));
+
+        assert!(questions.dismiss(0));
+        assert!(!questions.dismiss(0));
+        assert!(!questions.upvote(0, Voter::Connection(1)));
+        assert!(questions.list().is_empty());
+    }
+
+    #[test]
+    fn limits_the_waiting_questions_of_each_client() {
+        let mut questions = Questions::default();
+        let laptop = || Voter::Client("laptop".to_owned());
+
+        for _ in 0..MAX_QUESTIONS_PER_CLIENT {
+            assert!(questions.ask("Why?", laptop()));
+        }
+
+        assert!(!questions.ask("Why?", laptop()));
+        assert!(questions.ask("How?", Voter::Connection(1)));
+
+        assert!(questions.dismiss(0));
+        assert!(questions.ask("Why?", laptop()));
+    }
+
+    #[test]
+    fn forgets_the_questions_of_connections_that_left() {
+        let mut questions = Questions::default();
+
+        for _ in 0..MAX_QUESTIONS_PER_CLIENT {
+            assert!(questions.ask("Why?", Voter::Connection(1)));
+            assert!(questions.ask("How?", Voter::Client("phone".to_owned())));
+        }
+
+        questions.leave(1);
+
+        assert!(!questions.ask("How?", Voter::Client("phone".to_owned())));
+        assert_eq!(questions.list().len(), 2 * MAX_QUESTIONS_PER_CLIENT);
+        assert!(questions.ask("Why?", Voter::Connection(1)));
+    }
+}
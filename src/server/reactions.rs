@@ -0,0 +1,205 @@
+use {
+    super::roster::Voter,
+    crate::sync_message::Reaction,
+    std::{
+        collections::{BTreeMap, HashMap},
+        mem,
+        time::{Duration, Instant},
+    },
+};
+
+/// How many reactions a client can send in each `RATE_LIMIT_WINDOW`.
+const MAX_REACTIONS_PER_WINDOW: u32 = 5;
+
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(1);
+
+/// The reactions sent by the audience since they were last passed on to
+/// presenters.
+#[derive(Debug, Default)]
+pub(crate) struct Reactions {
+    pending: BTreeMap<Reaction, u32>,
+    /// The rate limit windows of clients, which outlive their connections so
+    /// that reconnecting doesn't start a new window.
+    windows: HashMap<Voter, RateWindow>,
+}
+
+#[derive(Debug)]
+struct RateWindow {
+    start: Instant,
+    count: u32,
+}
+
+impl Reactions {
+    /// Counts a client's reaction, unless the client has sent too many
+    /// recently.
+    ///
+    /// Returns `false` if the reaction was dropped.
+    pub fn react(&mut self, client: Voter, reaction: Reaction) -> bool {
+        self.react_at(client, reaction, Instant::now())
+    }
+
+    fn react_at(
+        &mut self,
+        client: Voter,
+        reaction: Reaction,
+        now: Instant,
+    ) -> bool {
+        let window = self.windows.entry(client).or_insert(RateWindow {
+            start: now,
+            count: 0,
+        });
+
+        if now.duration_since(window.start) >= RATE_LIMIT_WINDOW {
+            window.start = now;
+            window.count = 0;
+        }
+
+        if window.count >= MAX_REACTIONS_PER_WINDOW {
+            return false;
+        }
+
+        window.count += 1;
+        *self.pending.entry(reaction).or_default() += 1;
+
+        true
+    }
+
+    /// Forgets the window of a client that disconnected, if it can't be
+    /// recognized when it reconnects, and the windows that are over.
+    pub fn leave(&mut self, id: usize) {
+        self.leave_at(id, Instant::now());
+    }
+
+    fn leave_at(&mut self, id: usize, now: Instant) {
+        self.windows.remove(&Voter::Connection(id));
+        self.windows.retain(|_, window| {
+            now.duration_since(window.start) < RATE_LIMIT_WINDOW
+        });
+    }
+
+    /// Takes the reactions counted since the last call, if there are any.
+    pub fn take(&mut self) -> Option<BTreeMap<Reaction, u32>> {
+        if self.pending.is_empty() {
+            None
+        } else {
+            Some(mem::take(&mut self.pending))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Reaction, Reactions, Voter, MAX_REACTIONS_PER_WINDOW, RATE_LIMIT_WINDOW,
+    };
+    use std::time::{Duration, Instant};
+
+    fn react_up_to_the_limit(
+        reactions: &mut Reactions,
+        client: &Voter,
+        now: Instant,
+    ) {
+        for _ in 0..MAX_REACTIONS_PER_WINDOW {
+            assert!(reactions.react_at(
+                client.clone(),
+                Reaction::Applause,
+                now,
+            ));
+        }
+    }
+
+    fn laptop() -> Voter {
+        Voter::Client("laptop".to_owned())
+    }
+
+    #[test]
+    fn drops_reactions_over_the_limit_within_a_window() {
+        let mut reactions = Reactions::default();
+        let start = Instant::now();
+
+        react_up_to_the_limit(&mut reactions, &laptop(), start);
+
+        let almost_over = start + RATE_LIMIT_WINDOW - Duration::from_millis(1);
+
+        assert!(!reactions.react_at(laptop(), Reaction::Laugh, start));
+        assert!(!reactions.react_at(laptop(), Reaction::Laugh, almost_over));
+
+        let pending = reactions.take().unwrap();
+
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[&Reaction::Applause], MAX_REACTIONS_PER_WINDOW);
+    }
+
+    #[test]
+    fn accepts_reactions_again_once_the_window_is_over() {
+        let mut reactions = Reactions::default();
+        let start = Instant::now();
+
+        react_up_to_the_limit(&mut reactions, &laptop(), start);
+
+        assert!(!reactions.react_at(laptop(), Reaction::Love, start));
+
+        react_up_to_the_limit(
+            &mut reactions,
+            &laptop(),
+            start + RATE_LIMIT_WINDOW,
+        );
+
+        assert_eq!(
+            reactions.take().unwrap()[&Reaction::Applause],
+            2 * MAX_REACTIONS_PER_WINDOW,
+        );
+    }
+
+    #[test]
+    fn limits_each_client_separately() {
+        let mut reactions = Reactions::default();
+        let now = Instant::now();
+
+        react_up_to_the_limit(&mut reactions, &laptop(), now);
+        react_up_to_the_limit(&mut reactions, &Voter::Connection(2), now);
+
+        assert!(!reactions.react_at(laptop(), Reaction::Surprise, now));
+        assert!(!reactions.react_at(
+            Voter::Connection(2),
+            Reaction::Surprise,
+            now,
+        ));
+    }
+
+    #[test]
+    fn keeps_the_window_of_clients_that_reconnect() {
+        let mut reactions = Reactions::default();
+        let start = Instant::now();
+
+        react_up_to_the_limit(&mut reactions, &laptop(), start);
+        reactions.leave_at(1, start);
+
+        assert!(!reactions.react_at(laptop(), Reaction::ThumbsUp, start));
+
+        reactions.leave_at(2, start + RATE_LIMIT_WINDOW);
+
+        assert!(reactions.windows.is_empty());
+    }
+
+    #[test]
+    fn forgets_the_window_of_connections_that_left() {
+        let mut reactions = Reactions::default();
+        let now = Instant::now();
+
+        react_up_to_the_limit(&mut reactions, &Voter::Connection(1), now);
+        reactions.leave_at(1, now);
+
+        assert!(reactions.windows.is_empty());
+    }
+
+    #[test]
+    fn takes_pending_reactions_only_once() {
+        let mut reactions = Reactions::default();
+
+        assert!(reactions.take().is_none());
+        assert!(reactions.react(laptop(), Reaction::Laugh));
+        assert_eq!(reactions.take().unwrap()[&Reaction::Laugh], 1);
+        assert!(reactions.take().is_none());
+    }
+}
@@ -0,0 +1,165 @@
+use {
+    crate::sync_message::{Role, RosterSummary, SyncPosition},
+    serde::Serialize,
+    std::{
+        collections::HashMap,
+        time::{SystemTime, UNIX_EPOCH},
+    },
+};
+
+/// The longest client id that is accepted, in bytes.
+const MAX_CLIENT_ID_LENGTH: usize = 64;
+
+/// The clients connected to a presentation, and the position they follow.
+#[derive(Debug, Default)]
+pub(crate) struct Roster {
+    clients: HashMap<usize, RosterEntry>,
+    client_ids: HashMap<usize, String>,
+    position: Option<SyncPosition>,
+    /// The client that sent the position.
+    presented_by: Option<usize>,
+}
+
+/// Who votes in polls, asks and upvotes questions, and reacts.
+///
+/// Clients are recognized by the id they keep across reconnections, so that
+/// reconnecting replaces their votes instead of adding to them, and doesn't
+/// lift their limits. Clients that
+/// don't send one are only recognized until they disconnect.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub(crate) enum Voter {
+    Client(String),
+    Connection(usize),
+}
+
+/// A connected client, as listed by the `/sync/clients` endpoint.
+#[derive(Clone, Debug, Serialize)]
+pub struct RosterEntry {
+    pub id: usize,
+    pub role: Role,
+    /// When the client connected, in seconds since the Unix epoch.
+    pub connected_at: u64,
+    pub position: Option<SyncPosition>,
+}
+
+/// The response of the `/sync/clients` endpoint.
+#[derive(Clone, Debug, Serialize)]
+pub struct RosterReport {
+    #[serde(flatten)]
+    pub summary: RosterSummary,
+    pub clients: Vec<RosterEntry>,
+}
+
+impl Roster {
+    /// The position last sent by a presenter.
+    pub fn position(&self) -> Option<SyncPosition> {
+        self.position
+    }
+
+    /// The position last sent by a presenter, unless the client sent it
+    /// itself.
+    pub fn position_for(&self, id: usize) -> Option<SyncPosition> {
+        self.position.filter(|_| self.presented_by != Some(id))
+    }
+
+    pub fn role(&self, id: usize) -> Option<Role> {
+        self.clients.get(&id).map(|client| client.role)
+    }
+
+    pub fn join(&mut self, id: usize) {
+        let connected_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+
+        self.clients.insert(
+            id,
+            RosterEntry {
+                id,
+                role: Role::Audience,
+                connected_at,
+                position: None,
+            },
+        );
+    }
+
+    pub fn leave(&mut self, id: usize) {
+        self.clients.remove(&id);
+        self.client_ids.remove(&id);
+    }
+
+    /// Records the id a client keeps across reconnections, ignoring ids
+    /// that are too long.
+    pub fn identify(&mut self, id: usize, client_id: String) {
+        if self.clients.contains_key(&id)
+            && !client_id.is_empty()
+            && client_id.len() <= MAX_CLIENT_ID_LENGTH
+        {
+            self.client_ids.insert(id, client_id);
+        }
+    }
+
+    pub fn voter(&self, id: usize) -> Voter {
+        match self.client_ids.get(&id) {
+            Some(client_id) => Voter::Client(client_id.clone()),
+            None => Voter::Connection(id),
+        }
+    }
+
+    pub fn set_status(
+        &mut self,
+        id: usize,
+        role: Role,
+        position: SyncPosition,
+    ) {
+        if let Some(client) = self.clients.get_mut(&id) {
+            client.role = role;
+            client.position = Some(position);
+        }
+    }
+
+    /// Records a position sent by a presenter for everyone to follow.
+    pub fn present(&mut self, id: usize, position: SyncPosition) {
+        if let Some(client) = self.clients.get_mut(&id) {
+            client.position = Some(position);
+        }
+
+        self.position = Some(position);
+        self.presented_by = Some(id);
+    }
+
+    /// Counts the clients, comparing the audience's slides against the
+    /// presenter's position.
+    pub fn summary(&self) -> RosterSummary {
+        let mut summary = RosterSummary::default();
+
+        for client in self.clients.values() {
+            match client.role {
+                Role::Presenter => summary.presenters += 1,
+                Role::Audience => {
+                    summary.audience += 1;
+
+                    if let (Some(presenter), Some(position)) =
+                        (self.position, client.position)
+                    {
+                        if presenter.slide != position.slide {
+                            summary.drifted += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        summary
+    }
+
+    pub fn report(&self) -> RosterReport {
+        let mut clients: Vec<_> = self.clients.values().cloned().collect();
+
+        clients.sort_by_key(|client| client.id);
+
+        RosterReport {
+            summary: self.summary(),
+            clients,
+        }
+    }
+}
@@ -1,66 +1,397 @@
 use {
+    super::{
+        analytics::SessionReport,
+        polls::Polls,
+        questions::Questions,
+        reactions::Reactions,
+        roster::{Roster, RosterReport},
+        timeline_recorder::TimelineRecorder,
+    },
+    crate::sync_message::{
+        Annotation, ClientMessage, PollResults, Question, Reaction, Role,
+        RosterSummary, ServerMessage, SyncPosition,
+    },
+    crate::timeline::Timeline,
     axum::{
         extract::{
-            ws::{Message, WebSocket, WebSocketUpgrade},
+            ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade},
             Extension,
         },
+        http::StatusCode,
         response::IntoResponse,
+        Json,
     },
     derive_more::{Display, Error, From},
     futures_util::{select, FutureExt, StreamExt},
     std::{
+        collections::BTreeMap,
         convert::TryInto,
         sync::{
             atomic::{AtomicUsize, Ordering},
-            Arc,
+            Arc, Mutex, MutexGuard,
+        },
+        time::Duration,
+    },
+    tokio::{
+        sync::{
+            broadcast::{self, error::RecvError},
+            watch, Notify,
         },
+        time::{self, Instant},
     },
-    tokio::sync::broadcast,
-    tracing::{error, info, trace, trace_span, Span},
+    tracing::{error, info, trace, trace_span, warn, Span},
 };
 
+/// The close code sent to clients when the server stops.
+const GOING_AWAY: u16 = 1001;
+
+/// How many position changes can be waiting to be sent to a client before
+/// it is sent the latest position instead.
+const POSITION_BACKLOG: usize = 16;
+
+/// How many annotations can be waiting to be sent to a client before older
+/// ones are skipped.
+const ANNOTATION_BACKLOG: usize = 64;
+
+/// How many poll results can be waiting to be sent to a client before it
+/// is sent all of the results instead.
+const POLL_RESULTS_BACKLOG: usize = 16;
+
+/// How many bursts of reactions can be waiting to be sent to a client before
+/// older ones are skipped.
+const REACTION_BACKLOG: usize = 16;
+
+/// The shortest ping and reaction intervals, which shorter ones are raised to.
+const MIN_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How the synchronization connections are limited, and what is recorded
+/// from them.
+#[derive(Clone, Debug)]
+pub struct SyncOptions {
+    /// How often clients are pinged to check that they are still there, at
+    /// least every 10 milliseconds.
+    pub ping_interval: Duration,
+    /// How long a client can stay silent, including not answering pings,
+    /// before it is disconnected.
+    pub idle_timeout: Duration,
+    /// How many clients can be connected at the same time.
+    pub max_connections: usize,
+    /// The secret clients must send with their status to present.
+    ///
+    /// Without one, any client is allowed to present, which is only safe when
+    /// the whole audience is trusted.
+    pub presenter_token: Option<String>,
+    /// Whether live poll results are sent to the audience, and not only to
+    /// presenters.
+    pub share_poll_results: bool,
+    /// How often the reactions of the audience are passed on to presenters,
+    /// counted together, at least every 10 milliseconds.
+    pub reaction_interval: Duration,
+    /// Whether the positions sent by presenters are recorded into a
+    /// timeline, which is also needed for session reports.
+    pub record_timeline: bool,
+    /// How long each slide should take, in seconds, by slide number, to
+    /// compare session reports against.
+    pub time_targets: BTreeMap<usize, f64>,
+}
+
+impl Default for SyncOptions {
+    fn default() -> Self {
+        SyncOptions {
+            ping_interval: Duration::from_secs(15),
+            idle_timeout: Duration::from_secs(45),
+            max_connections: 1000,
+            presenter_token: None,
+            share_poll_results: false,
+            reaction_interval: Duration::from_millis(500),
+            record_timeline: false,
+            time_targets: BTreeMap::new(),
+        }
+    }
+}
+
 pub struct SlidePresenter {
+    options: SyncOptions,
     position: broadcast::Sender<(usize, u16, u16)>,
+    annotations: broadcast::Sender<(usize, Annotation)>,
+    roster: Mutex<Roster>,
+    roster_summary: watch::Sender<RosterSummary>,
+    roster_summary_receiver: watch::Receiver<RosterSummary>,
+    polls: Mutex<Polls>,
+    poll_results: broadcast::Sender<PollResults>,
+    questions: Mutex<Questions>,
+    question_list: watch::Sender<Vec<Question>>,
+    question_list_receiver: watch::Receiver<Vec<Question>>,
+    reactions: Mutex<Reactions>,
+    reaction_bursts: broadcast::Sender<BTreeMap<Reaction, u32>>,
+    timeline: Option<Mutex<TimelineRecorder>>,
     id_counter: AtomicUsize,
+    connections: AtomicUsize,
+    disconnected: Notify,
+    shutdown: watch::Sender<bool>,
+    shutdown_receiver: watch::Receiver<bool>,
+}
+
+/// The broadcasts a client's handler listens to.
+struct Receivers {
+    position: broadcast::Receiver<(usize, u16, u16)>,
+    annotations: broadcast::Receiver<(usize, Annotation)>,
+    poll_results: broadcast::Receiver<PollResults>,
+    reaction_bursts: broadcast::Receiver<BTreeMap<Reaction, u32>>,
+}
+
+/// A connected client, which is counted until it is dropped.
+struct Connection {
+    id: usize,
+    presenter: Arc<SlidePresenter>,
 }
 
 impl SlidePresenter {
     pub fn new() -> Arc<Self> {
-        let (position, _) = broadcast::channel(1);
+        SlidePresenter::with_options(SyncOptions::default())
+    }
+
+    pub fn with_options(mut options: SyncOptions) -> Arc<Self> {
+        options.ping_interval =
+            Self::checked_interval("ping", options.ping_interval);
+        options.reaction_interval =
+            Self::checked_interval("reaction", options.reaction_interval);
+
+        let (position, _) = broadcast::channel(POSITION_BACKLOG);
+        let (annotations, _) = broadcast::channel(ANNOTATION_BACKLOG);
+        let (shutdown, shutdown_receiver) = watch::channel(false);
+        let (roster_summary, roster_summary_receiver) =
+            watch::channel(RosterSummary::default());
+        let (poll_results, _) = broadcast::channel(POLL_RESULTS_BACKLOG);
+        let (question_list, question_list_receiver) =
+            watch::channel(Vec::new());
+        let (reaction_bursts, _) = broadcast::channel(REACTION_BACKLOG);
+        let timeline = options
+            .record_timeline
+            .then(|| Mutex::new(TimelineRecorder::default()));
 
         Arc::new(SlidePresenter {
+            options,
             position,
+            annotations,
+            roster: Mutex::new(Roster::default()),
+            roster_summary,
+            roster_summary_receiver,
+            polls: Mutex::new(Polls::default()),
+            poll_results,
+            questions: Mutex::new(Questions::default()),
+            question_list,
+            question_list_receiver,
+            reactions: Mutex::new(Reactions::default()),
+            reaction_bursts,
+            timeline,
             id_counter: AtomicUsize::new(0),
+            connections: AtomicUsize::new(0),
+            disconnected: Notify::new(),
+            shutdown,
+            shutdown_receiver,
         })
     }
 
+    fn checked_interval(name: &str, interval: Duration) -> Duration {
+        if interval < MIN_INTERVAL {
+            warn!(
+                "Raising the {} interval of {:?} to {:?}",
+                name, interval, MIN_INTERVAL,
+            );
+
+            MIN_INTERVAL
+        } else {
+            interval
+        }
+    }
+
     pub async fn handler(
         web_socket_upgrade: WebSocketUpgrade,
         Extension(presenter): Extension<Arc<SlidePresenter>>,
-    ) -> impl IntoResponse {
-        let position_receiver = presenter.position.subscribe();
-        let id = presenter.id_counter.fetch_add(1, Ordering::Relaxed);
+    ) -> Result<impl IntoResponse, StatusCode> {
+        let connection =
+            presenter.connect().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+        let receivers = Receivers {
+            position: presenter.position.subscribe(),
+            annotations: presenter.annotations.subscribe(),
+            poll_results: presenter.poll_results.subscribe(),
+            reaction_bursts: presenter.reaction_bursts.subscribe(),
+        };
 
-        web_socket_upgrade.on_upgrade(move |web_socket| {
-            presenter.handle(id, web_socket, position_receiver)
-        })
+        Ok(web_socket_upgrade.on_upgrade(move |web_socket| {
+            presenter.handle(connection, web_socket, receivers)
+        }))
+    }
+
+    /// Sets the polls of the deck, by poll number, with how many options
+    /// each has. Votes for other polls or options are ignored.
+    pub fn set_polls(&self, polls: BTreeMap<u32, u32>) {
+        self.polls().set_options(polls);
+    }
+
+    /// Lists the connected clients as JSON.
+    pub async fn clients_handler(
+        Extension(presenter): Extension<Arc<SlidePresenter>>,
+    ) -> Json<RosterReport> {
+        Json(presenter.clients())
+    }
+
+    pub fn clients(&self) -> RosterReport {
+        self.roster().report()
+    }
+
+    /// Sends the timeline recorded so far as JSON, or 404 if the timeline
+    /// isn't being recorded.
+    pub async fn timeline_handler(
+        Extension(presenter): Extension<Arc<SlidePresenter>>,
+    ) -> Result<Json<Timeline>, StatusCode> {
+        presenter.timeline().map(Json).ok_or(StatusCode::NOT_FOUND)
+    }
+
+    /// The positions recorded so far, if `SyncOptions::record_timeline` is
+    /// set.
+    pub fn timeline(&self) -> Option<Timeline> {
+        let recorder = self.timeline.as_ref()?;
+        let recorder = recorder.lock().expect("Timeline lock was poisoned");
+
+        Some(recorder.timeline().clone())
+    }
+
+    /// Sends the report of the session so far as JSON, or 404 if the
+    /// timeline isn't being recorded.
+    pub async fn analytics_handler(
+        Extension(presenter): Extension<Arc<SlidePresenter>>,
+    ) -> Result<Json<SessionReport>, StatusCode> {
+        presenter
+            .session_report()
+            .map(Json)
+            .ok_or(StatusCode::NOT_FOUND)
+    }
+
+    /// Measures the time spent on each slide so far, if
+    /// `SyncOptions::record_timeline` is set.
+    pub fn session_report(&self) -> Option<SessionReport> {
+        let recorder = self.timeline.as_ref()?;
+        let recorder = recorder.lock().expect("Timeline lock was poisoned");
+
+        Some(SessionReport::new(
+            recorder.timeline(),
+            recorder.elapsed(),
+            &self.options.time_targets,
+        ))
+    }
+
+    /// Closes every connection and waits for their handlers to finish.
+    ///
+    /// New connections are refused from then on.
+    pub async fn shutdown(&self) {
+        // Only fails if there are no receivers, but the presenter keeps one.
+        let _ = self.shutdown.send(true);
+
+        while self.connections.load(Ordering::Acquire) > 0 {
+            self.disconnected.notified().await;
+        }
+    }
+
+    fn connect(self: &Arc<Self>) -> Option<Connection> {
+        if *self.shutdown_receiver.borrow() {
+            return None;
+        }
+
+        let connections = self.connections.fetch_add(1, Ordering::AcqRel);
+        let connection = Connection {
+            id: self.id_counter.fetch_add(1, Ordering::Relaxed),
+            presenter: self.clone(),
+        };
+
+        if connections >= self.options.max_connections {
+            warn!(
+                "Refusing connection because the limit of {} was reached",
+                self.options.max_connections,
+            );
+
+            None
+        } else {
+            self.update_roster(|roster| roster.join(connection.id));
+
+            Some(connection)
+        }
     }
 
     async fn handle(
         self: Arc<Self>,
-        id: usize,
+        connection: Connection,
         mut web_socket: WebSocket,
-        mut position_receiver: broadcast::Receiver<(usize, u16, u16)>,
+        mut receivers: Receivers,
     ) {
+        let id = connection.id;
         let span = trace_span!("WebSocket handler #{}", id);
+        let mut shutdown = self.shutdown_receiver.clone();
+        let mut roster_summary = self.roster_summary_receiver.clone();
+        let mut question_list = self.question_list_receiver.clone();
+        let mut heartbeat = time::interval_at(
+            Instant::now() + self.options.ping_interval,
+            self.options.ping_interval,
+        );
+        let mut reaction_flush = time::interval(self.options.reaction_interval);
+        let mut last_seen = Instant::now();
+
+        if self.options.share_poll_results {
+            if let Err(error) =
+                self.send_all_poll_results(&mut web_socket).await
+            {
+                error.report(&span);
+                return;
+            }
+        }
 
         loop {
             let result = select! {
                 message = web_socket.next().fuse() => {
-                    self.handle_message(id, message, &span)
+                    last_seen = Instant::now();
+
+                    self.handle_message(id, message, &mut web_socket, &span)
+                        .await
+                }
+                position = receivers.position.recv().fuse() => {
+                    self.handle_position(id, position, &mut web_socket, &span)
+                        .await
                 }
-                position = position_receiver.recv().fuse() => {
-                    Self::handle_position(id, position, &mut web_socket, &span).await
+                annotation = receivers.annotations.recv().fuse() => {
+                    Self::handle_annotation(id, annotation, &mut web_socket, &span)
+                        .await
+                }
+                _ = roster_summary.changed().fuse() => {
+                    let summary = roster_summary.borrow().clone();
+
+                    Self::send(ServerMessage::Roster(summary), &mut web_socket)
+                        .await
+                }
+                results = receivers.poll_results.recv().fuse() => {
+                    self.handle_poll_results(id, results, &mut web_socket, &span)
+                        .await
+                }
+                _ = question_list.changed().fuse() => {
+                    if self.roster().role(id) == Some(Role::Presenter) {
+                        self.send_questions(&mut web_socket).await
+                    } else {
+                        Ok(())
+                    }
+                }
+                reactions = receivers.reaction_bursts.recv().fuse() => {
+                    self.handle_reactions(id, reactions, &mut web_socket, &span)
+                        .await
+                }
+                _ = reaction_flush.tick().fuse() => {
+                    self.flush_reactions(id);
+                    Ok(())
+                }
+                _ = heartbeat.tick().fuse() => {
+                    self.check_heartbeat(last_seen, &mut web_socket).await
+                }
+                _ = shutdown.changed().fuse() => {
+                    Self::close(&mut web_socket, Error::ShuttingDown).await
                 }
             };
 
@@ -71,59 +402,540 @@ impl SlidePresenter {
         }
     }
 
-    fn handle_message(
+    async fn check_heartbeat(
+        &self,
+        last_seen: Instant,
+        web_socket: &mut WebSocket,
+    ) -> Result<(), Error> {
+        if last_seen.elapsed() >= self.options.idle_timeout {
+            return Err(Error::TimedOut);
+        }
+
+        web_socket
+            .send(Message::Ping(Vec::new()))
+            .await
+            .map_err(Error::Send)
+    }
+
+    /// Sends a close frame with the error's description, and then returns
+    /// the error to end the handler.
+    async fn close(
+        web_socket: &mut WebSocket,
+        error: Error,
+    ) -> Result<(), Error> {
+        let close_frame = CloseFrame {
+            code: GOING_AWAY,
+            reason: error.to_string().into(),
+        };
+
+        web_socket
+            .send(Message::Close(Some(close_frame)))
+            .await
+            .map_err(Error::Send)?;
+
+        Err(error)
+    }
+
+    async fn handle_message(
         &self,
         id: usize,
         maybe_message: Option<Result<Message, axum::Error>>,
+        web_socket: &mut WebSocket,
         span: &Span,
     ) -> Result<(), Error> {
         let message = maybe_message
             .ok_or(Error::Disconnected)?
             .map_err(Error::Receive)?;
 
-        if let Message::Binary(message_bytes) = message {
-            if message_bytes.len() == 4 {
-                let slide_index_bytes = message_bytes[0..2].try_into().unwrap();
-                let step_index_bytes = message_bytes[2..4].try_into().unwrap();
+        match message {
+            Message::Binary(message_bytes) => {
+                self.handle_binary_message(id, message_bytes, web_socket, span)
+                    .await
+            }
+            Message::Text(text) => {
+                self.handle_text_message(id, &text, web_socket, span).await
+            }
+            Message::Ping(_) | Message::Pong(_) | Message::Close(_) => Ok(()),
+        }
+    }
 
-                let slide_index = u16::from_be_bytes(slide_index_bytes);
-                let step_index = u16::from_be_bytes(step_index_bytes);
+    async fn handle_text_message(
+        &self,
+        id: usize,
+        text: &str,
+        web_socket: &mut WebSocket,
+        span: &Span,
+    ) -> Result<(), Error> {
+        let message = match serde_json::from_str(text) {
+            Ok(message) => message,
+            Err(error) => {
+                span.in_scope(|| warn!("Ignoring invalid message: {}", error));
+                return Ok(());
+            }
+        };
 
-                span.in_scope(|| {
-                    trace!("Received {}:{}", slide_index, step_index)
+        match message {
+            ClientMessage::Status {
+                role,
+                token,
+                client,
+                position,
+            } => {
+                let requested_role = role;
+                let role = self.authorize(role, token.as_deref(), span);
+                let refused = role != requested_role;
+                let previous_role = self.roster().role(id);
+
+                self.update_roster(|roster| {
+                    if let Some(client) = client {
+                        roster.identify(id, client);
+                    }
+
+                    roster.set_status(id, role, position)
                 });
 
-                self.position.send((id, slide_index, step_index))?;
+                if refused || previous_role != Some(role) {
+                    Self::send(
+                        ServerMessage::Role { role, refused },
+                        web_socket,
+                    )
+                    .await?;
+                }
+
+                if role == Role::Presenter && previous_role != Some(role) {
+                    self.send_questions(web_socket).await?;
+                    self.send_all_poll_results(web_socket).await?;
+                }
             }
+            ClientMessage::Annotate { annotation } => {
+                self.annotate(id, annotation, span)
+            }
+            ClientMessage::Vote { poll, option } => {
+                let voter = self.roster().voter(id);
+                let results = self.polls().vote(poll, voter, option);
+
+                match results {
+                    // Only fails if there are no receivers, but the voter's
+                    // handler has one.
+                    Some(results) => drop(self.poll_results.send(results)),
+                    None => span.in_scope(|| {
+                        warn!(
+                            "Ignoring vote for unknown option {} of poll {}",
+                            option, poll,
+                        )
+                    }),
+                }
+            }
+            ClientMessage::Ask { text } => {
+                let asker = self.roster().voter(id);
+
+                if !self
+                    .update_questions(|questions| questions.ask(&text, asker))
+                {
+                    span.in_scope(|| warn!("Ignoring question"));
+                }
+            }
+            ClientMessage::Upvote { question } => {
+                let voter = self.roster().voter(id);
+
+                self.moderate(id, span, |questions| {
+                    questions.upvote(question, voter)
+                })
+            }
+            ClientMessage::Dismiss { question } => {
+                self.moderate(id, span, |questions| questions.dismiss(question))
+            }
+            ClientMessage::React { reaction } => self.react(id, reaction, span),
         }
 
         Ok(())
     }
 
+    async fn handle_binary_message(
+        &self,
+        id: usize,
+        message_bytes: Vec<u8>,
+        web_socket: &mut WebSocket,
+        span: &Span,
+    ) -> Result<(), Error> {
+        if message_bytes.len() == 4 {
+            if self.roster().role(id) != Some(Role::Presenter) {
+                span.in_scope(|| warn!("Ignoring position from the audience"));
+                return Ok(());
+            }
+
+            let slide_index_bytes = message_bytes[0..2].try_into().unwrap();
+            let step_index_bytes = message_bytes[2..4].try_into().unwrap();
+
+            let slide_index = u16::from_be_bytes(slide_index_bytes);
+            let step_index = u16::from_be_bytes(step_index_bytes);
+
+            span.in_scope(|| trace!("Received {}:{}", slide_index, step_index));
+
+            self.present(
+                id,
+                SyncPosition {
+                    slide: slide_index,
+                    step: step_index,
+                },
+            )?;
+        } else if message_bytes.is_empty() {
+            let current_position = self.roster().position();
+
+            if let Some(SyncPosition { slide, step }) = current_position {
+                Self::send_position(slide, step, web_socket, span).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Records a presenter's position and sends it to everyone else.
+    fn present(&self, id: usize, position: SyncPosition) -> Result<(), Error> {
+        self.update_roster(|roster| roster.present(id, position));
+
+        if let Some(timeline) = &self.timeline {
+            timeline
+                .lock()
+                .expect("Timeline lock was poisoned")
+                .record(position);
+        }
+
+        self.position.send((id, position.slide, position.step))?;
+
+        Ok(())
+    }
+
     async fn handle_position(
+        &self,
         id: usize,
-        position: Result<(usize, u16, u16), broadcast::error::RecvError>,
+        position: Result<(usize, u16, u16), RecvError>,
         web_socket: &mut WebSocket,
         span: &Span,
     ) -> Result<(), Error> {
-        if let Ok((sender_id, slide, step)) = position {
-            if sender_id != id {
-                let mut message_bytes = Vec::with_capacity(4);
+        match position {
+            Ok((sender_id, slide, step)) => {
+                if sender_id != id {
+                    Self::send_position(slide, step, web_socket, span).await?;
+                }
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                span.in_scope(|| {
+                    warn!("Lagged behind by {} position updates", skipped)
+                });
 
-                message_bytes.extend(slide.to_be_bytes());
-                message_bytes.extend(step.to_be_bytes());
+                // The presenter that sent the latest position is already
+                // there, and may have moved on.
+                let current_position = self.roster().position_for(id);
 
-                span.in_scope(|| trace!("Sending {}:{}", slide, step));
+                if let Some(SyncPosition { slide, step }) = current_position {
+                    Self::send_position(slide, step, web_socket, span).await?;
+                }
+            }
+            Err(RecvError::Closed) => {
+                Self::close(web_socket, Error::Stopped).await?;
+            }
+        }
 
-                web_socket
-                    .send(Message::Binary(message_bytes))
-                    .await
-                    .map_err(Error::Send)?;
+        Ok(())
+    }
+
+    /// Checks that a client asking to present has the presenter token,
+    /// returning the role it is allowed to have.
+    fn authorize(&self, role: Role, token: Option<&str>, span: &Span) -> Role {
+        let expected_token = match (role, &self.options.presenter_token) {
+            (Role::Presenter, Some(expected_token)) => expected_token,
+            _ => return role,
+        };
+
+        if token.is_some_and(|token| tokens_match(token, expected_token)) {
+            role
+        } else {
+            span.in_scope(|| {
+                warn!("Refusing to let a client present without the token")
+            });
+
+            Role::Audience
+        }
+    }
+
+    fn annotate(&self, id: usize, annotation: Annotation, span: &Span) {
+        if self.roster().role(id) != Some(Role::Presenter) {
+            span.in_scope(|| warn!("Ignoring annotation from the audience"));
+            return;
+        }
+
+        // Only fails if there are no receivers, but the sender's handler has
+        // one.
+        let _ = self.annotations.send((id, annotation));
+    }
+
+    async fn handle_annotation(
+        id: usize,
+        annotation: Result<(usize, Annotation), RecvError>,
+        web_socket: &mut WebSocket,
+        span: &Span,
+    ) -> Result<(), Error> {
+        match annotation {
+            Ok((sender_id, annotation)) => {
+                if sender_id != id {
+                    let message = ServerMessage::Annotate { annotation };
+
+                    Self::send(message, web_socket).await?;
+                }
+            }
+            Err(RecvError::Lagged(skipped)) => span.in_scope(|| {
+                warn!("Skipped {} annotations to catch up", skipped)
+            }),
+            Err(RecvError::Closed) => {
+                Self::close(web_socket, Error::Stopped).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether a client is sent live poll results.
+    fn receives_poll_results(&self, id: usize) -> bool {
+        self.options.share_poll_results
+            || self.roster().role(id) == Some(Role::Presenter)
+    }
+
+    async fn handle_poll_results(
+        &self,
+        id: usize,
+        results: Result<PollResults, RecvError>,
+        web_socket: &mut WebSocket,
+        span: &Span,
+    ) -> Result<(), Error> {
+        match results {
+            Ok(results) => {
+                if self.receives_poll_results(id) {
+                    Self::send(ServerMessage::PollResults(results), web_socket)
+                        .await?;
+                }
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                span.in_scope(|| {
+                    warn!("Lagged behind by {} poll results", skipped)
+                });
+
+                if self.receives_poll_results(id) {
+                    self.send_all_poll_results(web_socket).await?;
+                }
+            }
+            Err(RecvError::Closed) => {
+                Self::close(web_socket, Error::Stopped).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn send_all_poll_results(
+        &self,
+        web_socket: &mut WebSocket,
+    ) -> Result<(), Error> {
+        let all_results = self.polls().all_results();
+
+        for results in all_results {
+            Self::send(ServerMessage::PollResults(results), web_socket).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Changes the Q&A queue on behalf of a presenter.
+    fn moderate(
+        &self,
+        id: usize,
+        span: &Span,
+        update: impl FnOnce(&mut Questions) -> bool,
+    ) {
+        if self.roster().role(id) != Some(Role::Presenter) {
+            span.in_scope(|| warn!("Ignoring moderation from the audience"));
+            return;
+        }
+
+        if !self.update_questions(update) {
+            span.in_scope(|| {
+                warn!("Ignoring repeated moderation or unknown question")
+            });
+        }
+    }
+
+    /// Changes the Q&A queue, and then tells the presenters about the change
+    /// if `update` returns `true`.
+    fn update_questions(
+        &self,
+        update: impl FnOnce(&mut Questions) -> bool,
+    ) -> bool {
+        let mut questions = self.questions();
+
+        if !update(&mut questions) {
+            return false;
+        }
+
+        // Only fails if there are no receivers, but the presenter keeps one.
+        let _ = self.question_list.send(questions.list());
+
+        true
+    }
+
+    async fn send_questions(
+        &self,
+        web_socket: &mut WebSocket,
+    ) -> Result<(), Error> {
+        let questions = self.question_list_receiver.borrow().clone();
+
+        Self::send(ServerMessage::Questions { questions }, web_socket).await
+    }
+
+    fn react(&self, id: usize, reaction: Reaction, span: &Span) {
+        // Nobody would see the reaction, and it shouldn't be shown to a
+        // presenter who joins later.
+        if self.roster_summary_receiver.borrow().presenters == 0 {
+            return;
+        }
+
+        let voter = self.roster().voter(id);
+
+        if !self.reactions().react(voter, reaction) {
+            span.in_scope(|| trace!("Dropping reaction over the rate limit"));
+        }
+    }
+
+    /// Passes the reactions counted since the last flush on to presenters.
+    ///
+    /// Every presenter's handler flushes periodically, so that the reactions
+    /// are sent as long as someone is presenting.
+    fn flush_reactions(&self, id: usize) {
+        if self.roster().role(id) != Some(Role::Presenter) {
+            return;
+        }
+
+        let reactions = self.reactions().take();
+
+        if let Some(reactions) = reactions {
+            // Only fails if there are no receivers, but the presenter's
+            // handler has one.
+            let _ = self.reaction_bursts.send(reactions);
+        }
+    }
+
+    async fn handle_reactions(
+        &self,
+        id: usize,
+        reactions: Result<BTreeMap<Reaction, u32>, RecvError>,
+        web_socket: &mut WebSocket,
+        span: &Span,
+    ) -> Result<(), Error> {
+        match reactions {
+            Ok(reactions) => {
+                if self.roster().role(id) == Some(Role::Presenter) {
+                    let message = ServerMessage::Reactions { reactions };
+
+                    Self::send(message, web_socket).await?;
+                }
+            }
+            Err(RecvError::Lagged(skipped)) => span.in_scope(|| {
+                warn!("Skipped {} bursts of reactions to catch up", skipped)
+            }),
+            Err(RecvError::Closed) => {
+                Self::close(web_socket, Error::Stopped).await?;
             }
         }
 
         Ok(())
     }
+
+    async fn send_position(
+        slide: u16,
+        step: u16,
+        web_socket: &mut WebSocket,
+        span: &Span,
+    ) -> Result<(), Error> {
+        let mut message_bytes = Vec::with_capacity(4);
+
+        message_bytes.extend(slide.to_be_bytes());
+        message_bytes.extend(step.to_be_bytes());
+
+        span.in_scope(|| trace!("Sending {}:{}", slide, step));
+
+        web_socket
+            .send(Message::Binary(message_bytes))
+            .await
+            .map_err(Error::Send)
+    }
+
+    async fn send(
+        message: ServerMessage,
+        web_socket: &mut WebSocket,
+    ) -> Result<(), Error> {
+        let text = serde_json::to_string(&message).map_err(Error::Serialize)?;
+
+        web_socket
+            .send(Message::Text(text))
+            .await
+            .map_err(Error::Send)
+    }
+
+    /// Changes the roster, and then tells every client about the change.
+    fn update_roster(&self, update: impl FnOnce(&mut Roster)) {
+        let mut roster = self.roster();
+
+        update(&mut roster);
+
+        let summary = roster.summary();
+
+        if *self.roster_summary_receiver.borrow() != summary {
+            // Only fails if there are no receivers, but the presenter keeps
+            // one.
+            let _ = self.roster_summary.send(summary);
+        }
+    }
+
+    fn roster(&self) -> MutexGuard<'_, Roster> {
+        self.roster.lock().expect("Roster lock was poisoned")
+    }
+
+    fn questions(&self) -> MutexGuard<'_, Questions> {
+        self.questions.lock().expect("Questions lock was poisoned")
+    }
+
+    fn polls(&self) -> MutexGuard<'_, Polls> {
+        self.polls.lock().expect("Polls lock was poisoned")
+    }
+
+    fn reactions(&self) -> MutexGuard<'_, Reactions> {
+        self.reactions.lock().expect("Reactions lock was poisoned")
+    }
+}
+
+/// Compares tokens in a time that doesn't depend on how much of them match.
+fn tokens_match(token: &str, expected_token: &str) -> bool {
+    token.len() == expected_token.len()
+        && token
+            .bytes()
+            .zip(expected_token.bytes())
+            .fold(0, |difference, (byte, expected)| {
+                difference | (byte ^ expected)
+            })
+            == 0
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let presenter = &self.presenter;
+
+        presenter.update_roster(|roster| roster.leave(self.id));
+        presenter.questions().leave(self.id);
+        presenter.reactions().leave(self.id);
+
+        if presenter.connections.fetch_sub(1, Ordering::AcqRel) == 1 {
+            presenter.disconnected.notify_one();
+        }
+    }
 }
 
 #[derive(Debug, Display, Error, From)]
@@ -131,9 +943,22 @@ pub enum Error {
     #[display(fmt = "Client has disconnected")]
     Disconnected,
 
+    #[display(fmt = "Client stopped responding")]
+    TimedOut,
+
+    #[display(fmt = "Closed connection because the server is shutting down")]
+    ShuttingDown,
+
+    #[display(fmt = "Closed connection because synchronization has stopped")]
+    Stopped,
+
     #[display(fmt = "Failed to synchronize position internally")]
     Internal(broadcast::error::SendError<(usize, u16, u16)>),
 
+    #[display(fmt = "Failed to serialize message to a client")]
+    #[from(ignore)]
+    Serialize(serde_json::Error),
+
     #[display(fmt = "Failed to receive updated position from client")]
     #[from(ignore)]
     Receive(axum::Error),
@@ -146,10 +971,119 @@ pub enum Error {
 impl Error {
     pub fn report(&self, span: &Span) {
         span.in_scope(|| match self {
-            Error::Disconnected => info!("{}", self),
-            Error::Internal(_) | Error::Receive(_) | Error::Send(_) => {
-                error!("{}", self)
-            }
+            Error::Disconnected
+            | Error::TimedOut
+            | Error::ShuttingDown
+            | Error::Stopped => info!("{}", self),
+            Error::Internal(_)
+            | Error::Serialize(_)
+            | Error::Receive(_)
+            | Error::Send(_) => error!("{}", self),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{
+            tokens_match, SlidePresenter, SyncOptions, MIN_INTERVAL,
+            POSITION_BACKLOG,
+        },
+        crate::sync_message::{Role, SyncPosition},
+        std::{convert::TryInto, time::Duration},
+        tokio::sync::broadcast::error::TryRecvError,
+        tracing::Span,
+    };
+
+    #[test]
+    fn raises_intervals_too_short_to_tick() {
+        let presenter = SlidePresenter::with_options(SyncOptions {
+            ping_interval: Duration::ZERO,
+            reaction_interval: Duration::from_millis(1),
+            ..SyncOptions::default()
+        });
+
+        assert_eq!(presenter.options.ping_interval, MIN_INTERVAL);
+        assert_eq!(presenter.options.reaction_interval, MIN_INTERVAL);
+    }
+
+    #[test]
+    fn keeps_longer_intervals() {
+        let options = SyncOptions::default();
+        let presenter = SlidePresenter::with_options(options.clone());
+
+        assert_eq!(presenter.options.ping_interval, options.ping_interval);
+        assert_eq!(
+            presenter.options.reaction_interval,
+            options.reaction_interval,
+        );
+    }
+
+    #[test]
+    fn matches_only_identical_tokens() {
+        assert!(tokens_match("s3cret", "s3cret"));
+        assert!(!tokens_match("s3creT", "s3cret"));
+        assert!(!tokens_match("s3cre", "s3cret"));
+        assert!(!tokens_match("", "s3cret"));
+    }
+
+    #[test]
+    fn lets_only_clients_with_the_token_present() {
+        let presenter = SlidePresenter::with_options(SyncOptions {
+            presenter_token: Some("s3cret".to_owned()),
+            ..SyncOptions::default()
+        });
+        let span = Span::none();
+
+        assert_eq!(
+            presenter.authorize(Role::Presenter, Some("s3cret"), &span),
+            Role::Presenter,
+        );
+        assert_eq!(
+            presenter.authorize(Role::Presenter, Some("guess"), &span),
+            Role::Audience,
+        );
+        assert_eq!(
+            presenter.authorize(Role::Presenter, None, &span),
+            Role::Audience,
+        );
+        assert_eq!(
+            presenter.authorize(Role::Audience, None, &span),
+            Role::Audience,
+        );
+    }
+
+    #[test]
+    fn lets_anyone_present_without_a_token() {
+        let presenter = SlidePresenter::new();
+
+        assert_eq!(
+            presenter.authorize(Role::Presenter, None, &Span::none()),
+            Role::Presenter,
+        );
+    }
+
+    #[test]
+    fn resyncs_lagging_clients_without_echoing_the_presenter() {
+        let presenter = SlidePresenter::new();
+        let mut receiver = presenter.position.subscribe();
+        let last_step: u16 = POSITION_BACKLOG.try_into().unwrap();
+
+        for step in 0..=last_step {
+            presenter
+                .present(1, SyncPosition { slide: 0, step })
+                .unwrap();
+        }
+
+        assert!(matches!(receiver.try_recv(), Err(TryRecvError::Lagged(1))));
+        assert_eq!(presenter.roster().position_for(1), None);
+        assert_eq!(
+            presenter.roster().position_for(2),
+            Some(SyncPosition {
+                slide: 0,
+                step: last_step,
+            }),
+        );
+    }
+}
@@ -0,0 +1,50 @@
+use {
+    crate::{
+        sync_message::SyncPosition,
+        timeline::{Timeline, TimelineEntry},
+    },
+    std::time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Records the positions sent by presenters, timed from the first one.
+#[derive(Debug, Default)]
+pub(crate) struct TimelineRecorder {
+    start: Option<Instant>,
+    timeline: Timeline,
+}
+
+impl TimelineRecorder {
+    pub fn record(&mut self, position: SyncPosition) {
+        let last_position =
+            self.timeline.entries.last().map(|entry| entry.position);
+
+        if last_position == Some(position) {
+            return;
+        }
+
+        let now = Instant::now();
+
+        if self.start.is_none() {
+            self.timeline.started_at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs());
+        }
+
+        let start = *self.start.get_or_insert(now);
+
+        self.timeline.entries.push(TimelineEntry {
+            time: now.duration_since(start).as_secs_f64(),
+            position,
+        });
+    }
+
+    pub fn timeline(&self) -> &Timeline {
+        &self.timeline
+    }
+
+    /// How long it's been since the first position, in seconds.
+    pub fn elapsed(&self) -> f64 {
+        self.start
+            .map_or(0.0, |start| start.elapsed().as_secs_f64())
+    }
+}
@@ -0,0 +1,561 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Display, Formatter},
+    iter::Peekable,
+    str::{CharIndices, FromStr},
+};
+
+pub const STEPS_ATTRIBUTE: &str = "data-slide-steps";
+pub const LABEL_ATTRIBUTE: &str = "data-step-label";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StepSpec {
+    spec: String,
+    items: Vec<(usize, StepItem)>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StepItem {
+    Single(StepBound),
+    Range {
+        start: Option<StepBound>,
+        end: Option<StepBound>,
+    },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StepBound {
+    Absolute(usize),
+    Relative(usize),
+    Label { name: String, offset: usize },
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StepRange {
+    pub start: usize,
+    pub end: Option<usize>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StepContext {
+    previous_step: usize,
+    labels: HashMap<String, usize>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StepSpecError {
+    pub spec: String,
+    pub position: usize,
+    pub kind: StepSpecErrorKind,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StepSpecErrorKind {
+    Empty,
+    ExpectedStep,
+    ZeroStep,
+    ZeroOffset,
+    InvalidNumber(String),
+    UnexpectedCharacter(char),
+    InvertedRange { start: usize, end: usize },
+    UnknownLabel(String),
+    Overflow,
+}
+
+impl StepSpec {
+    pub fn parse(spec: &str) -> Result<Self, StepSpecError> {
+        Parser::new(spec).parse()
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = &StepItem> {
+        self.items.iter().map(|(_, item)| item)
+    }
+
+    pub fn resolve(
+        &self,
+        context: &StepContext,
+    ) -> Result<Vec<StepRange>, StepSpecError> {
+        self.items
+            .iter()
+            .map(|(position, item)| {
+                item.resolve(context).map_err(|kind| {
+                    StepSpecError::new(&self.spec, *position, kind)
+                })
+            })
+            .collect()
+    }
+}
+
+impl FromStr for StepSpec {
+    type Err = StepSpecError;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        StepSpec::parse(spec)
+    }
+}
+
+impl StepItem {
+    fn resolve(
+        &self,
+        context: &StepContext,
+    ) -> Result<StepRange, StepSpecErrorKind> {
+        let (start, end) = match self {
+            StepItem::Single(bound) => {
+                let step = bound.resolve(context)?;
+
+                (step, Some(step))
+            }
+            StepItem::Range { start, end } => {
+                let start = match start {
+                    Some(bound) => bound.resolve(context)?,
+                    None => 1,
+                };
+                let end = match end {
+                    Some(bound) => Some(bound.resolve(context)?),
+                    None => None,
+                };
+
+                (start, end)
+            }
+        };
+
+        match end {
+            Some(end) if end < start => {
+                Err(StepSpecErrorKind::InvertedRange { start, end })
+            }
+            _ => Ok(StepRange { start, end }),
+        }
+    }
+}
+
+impl StepBound {
+    fn resolve(
+        &self,
+        context: &StepContext,
+    ) -> Result<usize, StepSpecErrorKind> {
+        match self {
+            StepBound::Absolute(step) => Ok(*step),
+            StepBound::Relative(offset) => context
+                .previous_step
+                .checked_add(*offset)
+                .ok_or(StepSpecErrorKind::Overflow),
+            StepBound::Label { name, offset } => context
+                .labels
+                .get(name)
+                .ok_or_else(|| StepSpecErrorKind::UnknownLabel(name.clone()))?
+                .checked_add(*offset)
+                .ok_or(StepSpecErrorKind::Overflow),
+        }
+    }
+}
+
+impl StepRange {
+    pub fn last_known_step(&self) -> usize {
+        self.end.unwrap_or(self.start)
+    }
+
+    pub fn contains(&self, step: usize) -> bool {
+        step >= self.start && !matches!(self.end, Some(end) if step > end)
+    }
+}
+
+impl StepContext {
+    pub fn new() -> Self {
+        StepContext::default()
+    }
+
+    pub fn advance(&mut self, ranges: &[StepRange]) {
+        if let Some(last_step) =
+            ranges.iter().map(StepRange::last_known_step).max()
+        {
+            self.previous_step = last_step;
+        }
+    }
+
+    pub fn define_label(
+        &mut self,
+        name: impl Into<String>,
+        ranges: &[StepRange],
+    ) {
+        if let Some(first_step) = ranges.iter().map(|range| range.start).min() {
+            self.labels.insert(name.into(), first_step);
+        }
+    }
+}
+
+impl StepSpecError {
+    fn new(spec: &str, position: usize, kind: StepSpecErrorKind) -> Self {
+        StepSpecError {
+            spec: spec.to_owned(),
+            position,
+            kind,
+        }
+    }
+}
+
+impl Display for StepSpecError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "Invalid step specification \"{}\" at position {}: {}",
+            self.spec, self.position, self.kind,
+        )
+    }
+}
+
+impl Error for StepSpecError {}
+
+impl Display for StepSpecErrorKind {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            StepSpecErrorKind::Empty => write!(formatter, "empty step"),
+            StepSpecErrorKind::ExpectedStep => {
+                write!(formatter, "expected a step number, label or '+'")
+            }
+            StepSpecErrorKind::ZeroStep => {
+                write!(formatter, "steps are numbered from 1")
+            }
+            StepSpecErrorKind::ZeroOffset => {
+                write!(formatter, "step offsets must be at least 1")
+            }
+            StepSpecErrorKind::InvalidNumber(number) => {
+                write!(formatter, "invalid step number {}", number)
+            }
+            StepSpecErrorKind::UnexpectedCharacter(character) => {
+                write!(formatter, "unexpected character '{}'", character)
+            }
+            StepSpecErrorKind::InvertedRange { start, end } => write!(
+                formatter,
+                "range ends at step {} before it starts at step {}",
+                end, start,
+            ),
+            StepSpecErrorKind::UnknownLabel(label) => {
+                write!(formatter, "unknown step label \"{}\"", label)
+            }
+            StepSpecErrorKind::Overflow => {
+                write!(formatter, "step number is too large")
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    spec: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(spec: &'a str) -> Self {
+        Parser {
+            spec,
+            chars: spec.char_indices().peekable(),
+        }
+    }
+
+    fn parse(mut self) -> Result<StepSpec, StepSpecError> {
+        let mut items = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+
+            let position = self.position();
+
+            items.push((position, self.parse_item()?));
+            self.skip_whitespace();
+
+            match self.chars.next() {
+                None => break,
+                Some((_, ',')) => continue,
+                Some((position, character)) => {
+                    return Err(self.error(
+                        position,
+                        StepSpecErrorKind::UnexpectedCharacter(character),
+                    ))
+                }
+            }
+        }
+
+        Ok(StepSpec {
+            spec: self.spec.to_owned(),
+            items,
+        })
+    }
+
+    fn parse_item(&mut self) -> Result<StepItem, StepSpecError> {
+        self.skip_whitespace();
+
+        let item_position = self.position();
+        let start = self.parse_bound()?;
+
+        self.skip_whitespace();
+
+        if let Some((_, '-')) = self.chars.peek() {
+            self.chars.next();
+            self.skip_whitespace();
+
+            let end = self.parse_bound()?;
+
+            Ok(StepItem::Range { start, end })
+        } else {
+            match start {
+                Some(bound) => Ok(StepItem::Single(bound)),
+                None => {
+                    let kind = match self.chars.peek() {
+                        None | Some((_, ',')) => StepSpecErrorKind::Empty,
+                        Some(_) => StepSpecErrorKind::ExpectedStep,
+                    };
+
+                    Err(self.error(item_position, kind))
+                }
+            }
+        }
+    }
+
+    fn parse_bound(&mut self) -> Result<Option<StepBound>, StepSpecError> {
+        match self.chars.peek().copied() {
+            Some((_, character)) if character.is_ascii_digit() => {
+                let position = self.position();
+                let step = self.parse_number()?;
+
+                if step == 0 {
+                    Err(self.error(position, StepSpecErrorKind::ZeroStep))
+                } else {
+                    Ok(Some(StepBound::Absolute(step)))
+                }
+            }
+            Some((_, '+')) => {
+                let offset = self.parse_offset()?;
+
+                Ok(Some(StepBound::Relative(offset)))
+            }
+            Some((_, character))
+                if character.is_alphabetic() || character == '_' =>
+            {
+                let name = self.parse_label();
+                let offset = match self.chars.peek() {
+                    Some((_, '+')) => self.parse_offset()?,
+                    _ => 0,
+                };
+
+                Ok(Some(StepBound::Label { name, offset }))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn parse_offset(&mut self) -> Result<usize, StepSpecError> {
+        self.chars.next();
+
+        match self.chars.peek().copied() {
+            Some((position, character)) if character.is_ascii_digit() => {
+                match self.parse_number()? {
+                    0 => {
+                        Err(self.error(position, StepSpecErrorKind::ZeroOffset))
+                    }
+                    offset => Ok(offset),
+                }
+            }
+            _ => Ok(1),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<usize, StepSpecError> {
+        let start = self.position();
+
+        while let Some((_, character)) = self.chars.peek() {
+            if character.is_ascii_digit() {
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+
+        let number = &self.spec[start..self.position()];
+
+        number.parse().map_err(|_| {
+            self.error(
+                start,
+                StepSpecErrorKind::InvalidNumber(number.to_owned()),
+            )
+        })
+    }
+
+    fn parse_label(&mut self) -> String {
+        let start = self.position();
+
+        while let Some((_, character)) = self.chars.peek() {
+            if character.is_alphanumeric() || *character == '_' {
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+
+        self.spec[start..self.position()].to_owned()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some((_, character)) = self.chars.peek() {
+            if character.is_whitespace() {
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn position(&mut self) -> usize {
+        self.chars
+            .peek()
+            .map_or(self.spec.len(), |(position, _)| *position)
+    }
+
+    fn error(&self, position: usize, kind: StepSpecErrorKind) -> StepSpecError {
+        StepSpecError::new(self.spec, position, kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StepContext, StepRange, StepSpec, StepSpecErrorKind};
+
+    fn resolve(spec: &str, context: &StepContext) -> Vec<StepRange> {
+        StepSpec::parse(spec)
+            .expect("valid step specification")
+            .resolve(context)
+            .expect("resolvable step specification")
+    }
+
+    fn range(start: usize, end: Option<usize>) -> StepRange {
+        StepRange { start, end }
+    }
+
+    fn parse_error(spec: &str) -> (usize, StepSpecErrorKind) {
+        let error = StepSpec::parse(spec).expect_err("invalid specification");
+
+        (error.position, error.kind)
+    }
+
+    #[test]
+    fn resolves_single_steps_and_ranges() {
+        let context = StepContext::new();
+
+        assert_eq!(
+            resolve("1, 3-5", &context),
+            vec![range(1, Some(1)), range(3, Some(5))],
+        );
+    }
+
+    #[test]
+    fn resolves_open_ranges() {
+        let context = StepContext::new();
+
+        assert_eq!(resolve("3-", &context), vec![range(3, None)]);
+        assert_eq!(resolve("-2", &context), vec![range(1, Some(2))]);
+        assert_eq!(resolve("-", &context), vec![range(1, None)]);
+    }
+
+    #[test]
+    fn resolves_offsets_from_the_previous_step() {
+        let mut context = StepContext::new();
+
+        assert_eq!(resolve("+", &context), vec![range(1, Some(1))]);
+
+        context.advance(&[range(2, Some(4))]);
+
+        assert_eq!(resolve("+", &context), vec![range(5, Some(5))]);
+        assert_eq!(resolve("+2-", &context), vec![range(6, None)]);
+    }
+
+    #[test]
+    fn resolves_labels_with_offsets() {
+        let mut context = StepContext::new();
+
+        context.define_label("intro", &[range(3, Some(4)), range(2, None)]);
+
+        assert_eq!(resolve("intro", &context), vec![range(2, Some(2))]);
+        assert_eq!(
+            resolve("intro+-intro+3", &context),
+            vec![range(3, Some(5))],
+        );
+    }
+
+    #[test]
+    fn reports_unknown_labels_at_their_item() {
+        let error = StepSpec::parse("1, missing")
+            .unwrap()
+            .resolve(&StepContext::new())
+            .unwrap_err();
+
+        assert_eq!(error.position, 3);
+        assert_eq!(
+            error.kind,
+            StepSpecErrorKind::UnknownLabel("missing".to_owned()),
+        );
+    }
+
+    #[test]
+    fn rejects_inverted_ranges() {
+        let error = StepSpec::parse("2, 5-3")
+            .unwrap()
+            .resolve(&StepContext::new())
+            .unwrap_err();
+
+        assert_eq!(error.position, 3);
+        assert_eq!(
+            error.kind,
+            StepSpecErrorKind::InvertedRange { start: 5, end: 3 },
+        );
+    }
+
+    #[test]
+    fn reports_parse_errors_at_their_position() {
+        assert_eq!(parse_error(""), (0, StepSpecErrorKind::Empty));
+        assert_eq!(parse_error("1,,2"), (2, StepSpecErrorKind::Empty));
+        assert_eq!(parse_error("1, *"), (3, StepSpecErrorKind::ExpectedStep));
+        assert_eq!(
+            parse_error("1 2"),
+            (2, StepSpecErrorKind::UnexpectedCharacter('2')),
+        );
+        assert_eq!(
+            parse_error("99999999999999999999999"),
+            (
+                0,
+                StepSpecErrorKind::InvalidNumber(
+                    "99999999999999999999999".to_owned()
+                ),
+            ),
+        );
+    }
+
+    #[test]
+    fn rejects_zero_steps_and_offsets() {
+        assert_eq!(parse_error("1-0"), (2, StepSpecErrorKind::ZeroStep));
+        assert_eq!(parse_error("+0"), (1, StepSpecErrorKind::ZeroOffset));
+        assert_eq!(parse_error("intro+0"), (6, StepSpecErrorKind::ZeroOffset));
+    }
+
+    #[test]
+    fn reports_overflowing_offsets() {
+        let mut context = StepContext::new();
+        let spec = format!("+{}", usize::MAX);
+
+        context.advance(&[range(1, None)]);
+        context.define_label("intro", &[range(1, None)]);
+
+        let error = StepSpec::parse(&spec)
+            .unwrap()
+            .resolve(&context)
+            .unwrap_err();
+
+        assert_eq!(error.kind, StepSpecErrorKind::Overflow);
+
+        let error = StepSpec::parse(&format!("intro{}", spec))
+            .unwrap()
+            .resolve(&context)
+            .unwrap_err();
+
+        assert_eq!(error.kind, StepSpecErrorKind::Overflow);
+    }
+}
@@ -0,0 +1,160 @@
+use {
+    serde::{Deserialize, Serialize},
+    std::collections::BTreeMap,
+};
+
+/// A message sent by a client over the `/sync` WebSocket as JSON text.
+///
+/// Position changes made by presenters are sent as four byte binary messages
+/// instead, with the slide and step as big endian `u16`s. The server ignores
+/// them unless the client is an authorized presenter.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ClientMessage {
+    /// Tells the server what the client is doing and where it is.
+    ///
+    /// Clients asking to present must send the server's presenter token, if
+    /// it has one, or they are treated as part of the audience.
+    Status {
+        role: Role,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token: Option<String>,
+        /// A random id the client keeps across reconnections, which its
+        /// votes, upvotes, questions and reactions are counted by.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        client: Option<String>,
+        #[serde(flatten)]
+        position: SyncPosition,
+    },
+    /// Draws on the slide, which the server passes on to everyone else if the
+    /// client is an authorized presenter.
+    Annotate { annotation: Annotation },
+    /// Votes for an option of a poll, replacing the client's previous vote,
+    /// which is recognized by the id the client sent with its status.
+    Vote { poll: u32, option: u32 },
+    /// Adds a question to the Q&A queue.
+    Ask { text: String },
+    /// Upvotes a question in the Q&A queue, if the client is an authorized
+    /// presenter.
+    Upvote { question: u32 },
+    /// Removes a question from the Q&A queue, if the client is an authorized
+    /// presenter.
+    Dismiss { question: u32 },
+    /// Reacts to the presentation, which the server passes on to presenters.
+    React { reaction: Reaction },
+}
+
+/// A message sent by the server over the `/sync` WebSocket as JSON text.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ServerMessage {
+    /// Tells a client the role it was given when it changes, or when the
+    /// client asked to present without the presenter token and was refused.
+    Role {
+        role: Role,
+        refused: bool,
+    },
+    Roster(RosterSummary),
+    Annotate {
+        annotation: Annotation,
+    },
+    PollResults(PollResults),
+    Questions {
+        questions: Vec<Question>,
+    },
+    /// How many of each reaction the audience sent since the last message.
+    Reactions {
+        reactions: BTreeMap<Reaction, u32>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Role {
+    Presenter,
+    Audience,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SyncPosition {
+    pub slide: u16,
+    pub step: u16,
+}
+
+/// A pointer or a drawing shown over the current slide.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Annotation {
+    /// Moves the presenter's pointer, or hides it.
+    Pointer { point: Option<SlidePoint> },
+    /// Adds points to a freehand stroke, starting it if it's new.
+    Stroke { id: u32, points: Vec<SlidePoint> },
+    /// Removes all strokes.
+    Clear,
+}
+
+/// A point on a slide, from zero to one across its logical width and
+/// height.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SlidePoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Who is connected to a presentation.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RosterSummary {
+    pub presenters: usize,
+    pub audience: usize,
+    /// How many audience members are on a different slide than the
+    /// presenter.
+    pub drifted: usize,
+}
+
+/// The votes for each option of a poll, in the order of the options.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PollResults {
+    pub poll: u32,
+    pub votes: Vec<u32>,
+}
+
+/// A question asked by the audience, waiting to be answered.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Question {
+    pub id: u32,
+    pub text: String,
+    pub votes: u32,
+}
+
+/// A reaction the audience can send to presenters.
+#[derive(
+    Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Reaction {
+    Applause,
+    Laugh,
+    Love,
+    Surprise,
+    ThumbsUp,
+}
+
+impl Reaction {
+    pub const ALL: [Reaction; 5] = [
+        Reaction::Applause,
+        Reaction::Laugh,
+        Reaction::Love,
+        Reaction::Surprise,
+        Reaction::ThumbsUp,
+    ];
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Reaction::Applause => "👏",
+            Reaction::Laugh => "😂",
+            Reaction::Love => "❤️",
+            Reaction::Surprise => "😮",
+            Reaction::ThumbsUp => "👍",
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use {
+    crate::sync_message::SyncPosition,
+    serde::{Deserialize, Serialize},
+};
+
+/// The positions a presenter went through during a talk, to replay them
+/// alongside a recording of it.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Timeline {
+    /// When the first position was recorded, in seconds since the Unix
+    /// epoch.
+    pub started_at: u64,
+    /// The position changes, ordered by time.
+    pub entries: Vec<TimelineEntry>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TimelineEntry {
+    /// When the position was reached, in seconds since the first entry.
+    pub time: f64,
+    #[serde(flatten)]
+    pub position: SyncPosition,
+}
+
+impl Timeline {
+    /// The position the presenter was at after a number of seconds.
+    pub fn position_at(&self, time: f64) -> Option<SyncPosition> {
+        let reached = self.entries.partition_point(|entry| entry.time <= time);
+
+        self.entries
+            .get(reached.saturating_sub(1))
+            .map(|entry| entry.position)
+    }
+
+    /// When the last position was reached, in seconds.
+    pub fn duration(&self) -> f64 {
+        self.entries.last().map_or(0.0, |entry| entry.time)
+    }
+}